@@ -0,0 +1,86 @@
+//! Derive macros for [anvaya](https://github.com/nilaysavant/anvaya), re-exported by its
+//! prelude with the `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, Index, parse_macro_input, parse_quote};
+
+/// Derive `Bundle` for a struct, inserting each of its fields as a component.
+///
+/// Works for structs with named or unnamed fields, and generic ones (with `'static` fields).
+#[proc_macro_derive(Bundle)]
+pub fn derive_bundle(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    bundle_impl(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn bundle_impl(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`Bundle` can only be derived for structs",
+        ));
+    };
+    let (field_types, field_accessors): (Vec<_>, Vec<_>) = match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let ident = field.ident.as_ref().unwrap();
+                (&field.ty, quote!(#ident))
+            })
+            .unzip(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let index = Index::from(index);
+                (&field.ty, quote!(#index))
+            })
+            .unzip(),
+        Fields::Unit => (Vec::new(), Vec::new()),
+    };
+    let count = field_types.len();
+
+    // Add the world value param `__V` along with bounds for each field...
+    let mut generics = input.generics.clone();
+    generics
+        .params
+        .push(parse_quote!(__V: ?Sized + ::anvaya::prelude::WorldValue));
+    let where_clause = generics.make_where_clause();
+    for ty in &field_types {
+        where_clause.predicates.push(parse_quote!(#ty: 'static));
+        where_clause
+            .predicates
+            .push(parse_quote!(::anvaya::prelude::ThreadSafe<__V, #ty>: Send + Sync));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let ident = &input.ident;
+
+    Ok(quote! {
+        impl #impl_generics ::anvaya::prelude::Bundle<__V> for #ident #ty_generics #where_clause {
+            const COUNT: usize = #count;
+
+            fn insert_into<'__a, __B>(self, builder: &mut __B)
+            where
+                __B: ?Sized + ::anvaya::prelude::EntityBuilderMethods<'__a, Erased = __V>,
+            {
+                #(builder.insert(self.#field_accessors);)*
+            }
+
+            fn reserve_tables<'__a, __B>(
+                world: &mut ::anvaya::prelude::World<__B::Key, __B::EntityStorage>,
+                additional: usize,
+            ) where
+                __B: ?Sized + ::anvaya::prelude::EntityBuilderMethods<'__a, Erased = __V>,
+            {
+                #(__B::reserve_table::<#field_types>(world, additional);)*
+            }
+        }
+    })
+}
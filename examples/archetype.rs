@@ -0,0 +1,52 @@
+//! Example with the Archetype World backend.
+//!
+//! - Entities are grouped by their set of components.
+//! - Queries only visit archetypes matching the filters.
+
+use anvaya::prelude::*;
+
+fn main() {
+    #[derive(Debug)]
+    struct Position(f32, f32);
+    struct Velocity(f32, f32);
+    struct Frozen;
+
+    let mut world = ArchetypeWorld::new();
+    world
+        .spawn()
+        .insert(Position(0.0, 0.0))
+        .insert(Velocity(1.0, 0.5));
+    world
+        .spawn()
+        .insert(Position(5.0, 5.0))
+        .insert(Velocity(-1.0, 0.0));
+    let frozen = world
+        .spawn()
+        .insert(Position(2.0, 2.0))
+        .insert(Velocity(1.0, 1.0))
+        .insert(Frozen)
+        .id;
+
+    // Update loop...
+    for _ in 0..2 {
+        world
+            .query_mut()
+            .without::<Frozen>()
+            .get_mut::<(&mut Position, &Velocity)>()
+            .for_each(|(_, (position, velocity))| {
+                position.0 += velocity.0;
+                position.1 += velocity.1;
+            });
+    }
+
+    // Unfreeze, moves the entity to the (Position, Velocity) archetype...
+    world.remove_component::<Frozen>(frozen);
+
+    let positions = world
+        .query()
+        .get::<&Position>()
+        .map(|(_, position)| (position.0, position.1))
+        .collect::<Vec<_>>();
+    dbg!(&positions);
+    assert_eq!(positions, vec![(2.0, 1.0), (3.0, 5.0), (2.0, 2.0)]);
+}
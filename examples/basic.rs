@@ -30,7 +30,7 @@ fn main() {
     let Some(results) = query
         .with::<Player>()
         .with::<Age>()
-        .get::<Player>()
+        .get::<&Player>()
         .map(|results| results.collect::<Vec<_>>())
     else {
         return;
@@ -53,7 +53,7 @@ fn main() {
     let Some(results) = query
         .with::<Player>()
         .with::<Age>()
-        .get::<Player>()
+        .get::<&Player>()
         .map(|results| results.collect::<Vec<_>>())
     else {
         return;
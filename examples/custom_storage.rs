@@ -3,6 +3,8 @@
 //! - Using a wrapper of Slab instead of slab directly.
 //!
 
+use std::any::Any;
+
 use anvaya::prelude::*;
 use slab::Slab;
 
@@ -27,7 +29,7 @@ fn main() {
     let mut query = world.query();
     let results = query
         .with::<Abilities>() //
-        .get::<Player>()
+        .get::<&Player>()
         .unwrap()
         .map(|(_, p)| p.0)
         .collect::<Vec<_>>();
@@ -59,6 +61,10 @@ impl<T> Storage for MyStorage<T> {
         Self(Slab::with_capacity(capacity))
     }
 
+    fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+
     fn insert(&mut self, val: Self::Value) -> Self::Key {
         self.0.insert(val)
     }
@@ -71,6 +77,10 @@ impl<T> Storage for MyStorage<T> {
         self.0.get_mut(key)
     }
 
+    fn remove(&mut self, key: Self::Key) -> Option<Self::Value> {
+        self.0.try_remove(key)
+    }
+
     fn key_of(&self, val: &Self::Value) -> Self::Key {
         self.0.key_of(val)
     }
@@ -90,6 +100,10 @@ impl<T> Storage for MyStorage<T> {
     fn iter(&self) -> impl Iterator<Item = (Self::Key, &Self::Value)> {
         self.0.iter()
     }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = (Self::Key, &mut Self::Value)> {
+        self.0.iter_mut()
+    }
 }
 
 #[derive(Debug, Default)]
@@ -98,6 +112,8 @@ struct MyWorld(World<usize, MyStorage<TypeMap>>);
 impl WorldMethods for MyWorld {
     type Key = usize;
 
+    type Erased = dyn Any;
+
     type EntityStorage = MyStorage<TypeMap>;
 
     type ComponentStorage<T: 'static> = MyStorage<T>;
@@ -106,6 +122,8 @@ impl WorldMethods for MyWorld {
 
     type AssocQueryBuilder<'a> = MyQueryBuilder<'a>;
 
+    type AssocQueryMutBuilder<'a> = MyQueryMutBuilder<'a>;
+
     fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
         &self.0
     }
@@ -120,6 +138,8 @@ struct MyEntityBuilder<'a>(EntityBuilder<'a, usize, MyStorage<TypeMap>>);
 impl<'a> EntityBuilderMethods<'a> for MyEntityBuilder<'a> {
     type Key = usize;
 
+    type Erased = dyn Any;
+
     type EntityStorage = MyStorage<TypeMap>;
 
     type ComponentStorage<T: 'static> = MyStorage<T>;
@@ -142,6 +162,8 @@ struct MyQueryBuilder<'a>(QueryBuilder<'a, usize, MyStorage<TypeMap>>);
 impl<'a> QueryBuilderMethods<'a> for MyQueryBuilder<'a> {
     type Key = usize;
 
+    type Erased = dyn Any;
+
     type EntityStorage = MyStorage<TypeMap>;
 
     type ComponentStorage<T: 'static> = MyStorage<T>;
@@ -166,3 +188,39 @@ impl<'a> QueryBuilderMethods<'a> for MyQueryBuilder<'a> {
         self.0.world
     }
 }
+
+struct MyQueryMutBuilder<'a>(QueryMutBuilder<'a, usize, MyStorage<TypeMap>>);
+
+impl<'a> QueryMutBuilderMethods<'a> for MyQueryMutBuilder<'a> {
+    type Key = usize;
+
+    type Erased = dyn Any;
+
+    type EntityStorage = MyStorage<TypeMap>;
+
+    type ComponentStorage<T: 'static> = MyStorage<T>;
+
+    fn create(world: &'a mut World<Self::Key, Self::EntityStorage>) -> Self {
+        Self(QueryMutBuilder::new(world))
+    }
+
+    fn with_call_count(&mut self) -> &mut u32 {
+        &mut self.0.with_call_count
+    }
+
+    fn entity_freq(&self) -> &EntityFrequency<Self::Key> {
+        &self.0.entity_freq
+    }
+
+    fn entity_freq_mut(&mut self) -> &mut EntityFrequency<Self::Key> {
+        &mut self.0.entity_freq
+    }
+
+    fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
+        self.0.world
+    }
+
+    fn world_mut(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
+        self.0.world
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! - Players are filtered based on what they play. ie. (football or cricket).
 //! - Also filtered if they only meet the min age criteria.
+//! - Players of any sport are filtered in a single query.
 
 use anvaya::prelude::*;
 
@@ -31,42 +32,38 @@ fn main() {
         .insert(Cricketeer);
 
     let mut query = world.query();
-    let footballers_allowed = query
+    let footballers = query
         .with::<Footballer>()
-        .get::<Age>()
+        .get::<(&Player, &Age)>()
         .map(|results| {
             results
-                .filter(|(_, age)| age.0 >= 16)
-                .map(|(e, _)| e)
+                .filter(|(_, (_, age))| age.0 >= 16)
+                .map(|(_, (player, _))| player.0)
                 .collect::<Vec<_>>()
         })
         .unwrap();
     let mut query = world.query();
-    let cricketers_allowed = query
+    let cricketers = query
         .with::<Cricketeer>()
-        .get::<Age>()
+        .get::<(&Player, &Age)>()
         .map(|results| {
             results
-                .filter(|(_, age)| age.0 >= 15)
-                .map(|(e, _)| e)
+                .filter(|(_, (_, age))| age.0 >= 15)
+                .map(|(_, (player, _))| player.0)
                 .collect::<Vec<_>>()
         })
         .unwrap();
 
-    let mut footballers = vec![];
-    for entity in footballers_allowed.iter() {
-        let player = world.component_mut::<Player>(*entity).unwrap();
-        footballers.push(player.0);
-    }
-
-    let mut cricketers = vec![];
-    for entity in cricketers_allowed.iter() {
-        let player = world.component_mut::<Player>(*entity).unwrap();
-        cricketers.push(player.0);
-    }
+    let mut query = world.query();
+    let sports_players = query
+        .any_of::<(Footballer, Cricketeer)>()
+        .get::<&Player>()
+        .map(|results| results.map(|(_, player)| player.0).collect::<Vec<_>>())
+        .unwrap();
 
-    dbg!(&footballers, &cricketers);
+    dbg!(&footballers, &cricketers, &sports_players);
 
     assert_eq!(footballers, vec!["Rahul"]);
     assert_eq!(cricketers, vec!["Mike", "Rahul", "Sam"]);
+    assert_eq!(sports_players, vec!["Mike", "Rahul", "Sam"]);
 }
@@ -0,0 +1,764 @@
+use core::{
+    any::{Any, TypeId},
+    fmt::Debug,
+    mem,
+};
+use std::collections::{HashMap, HashSet};
+
+use crate::query_data::Access;
+
+/// World backend grouping entities by their exact set of components (archetype).
+///
+/// - Components of an archetype are stored in columns (one `Vec` per component type).
+/// - Entities are moved between archetypes when components are inserted or removed.
+/// - Queries only visit archetypes matching their filters, instead of every entity.
+///
+/// __Note: This is a separate, limited API, it does not implement
+/// [`WorldMethods`](crate::prelude::WorldMethods).__ Only spawning, inserting/removing
+/// components, despawning and queries (`with`/`without` filters) are supported. Resources,
+/// events, change detection, removal tracking, [`Commands`](crate::prelude::Commands),
+/// systems, schedules, bundles and snapshots only work with [`World`](crate::prelude::World)
+/// based worlds.
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// struct Player(&'static str);
+/// struct Age(u8);
+///
+/// let mut world = ArchetypeWorld::new();
+/// world.spawn().insert(Player("Mike")).insert(Age(30));
+/// world.spawn().insert(Player("Hannah"));
+///
+/// let query = world.query();
+/// let mut results = query
+///     .with::<Age>()
+///     .get::<&Player>()
+///     .map(|(_, player)| player.0);
+/// assert_eq!(results.next(), Some("Mike"));
+/// assert_eq!(results.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct ArchetypeWorld {
+    /// Location of each entity, indexed by entity id (`None` if despawned).
+    entities: Vec<Option<EntityLocation>>,
+    /// Ids of despawned entities available for re-use.
+    free_ids: Vec<usize>,
+    /// All archetypes, the first one being the empty archetype.
+    archetypes: Vec<Archetype>,
+    /// Archetype index keyed by its sorted component types.
+    archetype_ids: HashMap<Vec<TypeId>, usize>,
+}
+
+impl Default for ArchetypeWorld {
+    fn default() -> Self {
+        Self {
+            entities: Vec::new(),
+            free_ids: Vec::new(),
+            archetypes: vec![Archetype::default()],
+            archetype_ids: HashMap::from([(Vec::new(), 0)]),
+        }
+    }
+}
+
+impl ArchetypeWorld {
+    /// Create a new empty [`ArchetypeWorld`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn an entity (with no components) and return a builder to insert them.
+    pub fn spawn(&mut self) -> ArchetypeEntityBuilder<'_> {
+        let empty = &mut self.archetypes[0];
+        let location = EntityLocation {
+            archetype: 0,
+            row: empty.entities.len(),
+        };
+        let id = if let Some(id) = self.free_ids.pop() {
+            self.entities[id] = Some(location);
+            id
+        } else {
+            self.entities.push(Some(location));
+            self.entities.len() - 1
+        };
+        empty.entities.push(id);
+
+        ArchetypeEntityBuilder { id, world: self }
+    }
+
+    /// Number of alive entities.
+    pub fn len(&self) -> usize {
+        self.entities.len() - self.free_ids.len()
+    }
+
+    /// Check if there are no alive entities.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of archetypes, incl. the empty archetype.
+    pub fn archetype_count(&self) -> usize {
+        self.archetypes.len()
+    }
+
+    /// Check if the entity is alive.
+    pub fn contains(&self, entity: usize) -> bool {
+        self.location(entity).is_some()
+    }
+
+    /// Insert component `C` to the entity, returning the previous value if it already had one.
+    ///
+    /// Moves the entity to the archetype including `C` if needed.
+    ///
+    /// Panics if the entity does not exist.
+    pub fn insert<C: 'static>(&mut self, entity: usize, component: C) -> Option<C> {
+        let location = self
+            .location(entity)
+            .unwrap_or_else(|| panic!("Entity not found for id: {entity}"));
+        let archetype = &mut self.archetypes[location.archetype];
+        if let Some(column) = archetype.column_mut::<C>() {
+            return Some(mem::replace(&mut column[location.row], component));
+        }
+
+        let mut types = archetype.types.clone();
+        types.push(TypeId::of::<C>());
+        types.sort();
+        let dst = self.archetype_id(types, location.archetype, |columns| {
+            columns.insert(TypeId::of::<C>(), Box::new(Column::<C>(Vec::new())));
+        });
+        self.move_entity(entity, dst);
+        self.archetypes[dst]
+            .column_mut::<C>()
+            .expect("Column missing for inserted component")
+            .push(component);
+
+        None
+    }
+
+    /// Remove component `C` from the entity and return it.
+    ///
+    /// Moves the entity to the archetype excluding `C`.
+    pub fn remove_component<C: 'static>(&mut self, entity: usize) -> Option<C> {
+        let location = self.location(entity)?;
+        let archetype = &mut self.archetypes[location.archetype];
+        let component = archetype.column_mut::<C>()?.swap_remove(location.row);
+
+        let mut types = archetype.types.clone();
+        types.retain(|type_id| *type_id != TypeId::of::<C>());
+        let dst = self.archetype_id(types, location.archetype, |columns| {
+            columns.remove(&TypeId::of::<C>());
+        });
+        self.move_entity(entity, dst);
+
+        Some(component)
+    }
+
+    /// Despawn the entity, dropping all of its components.
+    ///
+    /// Returns `false` if the entity did not exist.
+    pub fn despawn(&mut self, entity: usize) -> bool {
+        let Some(location) = self.location(entity) else {
+            return false;
+        };
+        let archetype = &mut self.archetypes[location.archetype];
+        for column in archetype.columns.values_mut() {
+            column.swap_remove_drop(location.row);
+        }
+        self.remove_row(location);
+        self.entities[entity] = None;
+        self.free_ids.push(entity);
+
+        true
+    }
+
+    /// Get ref to component `C` of the entity.
+    pub fn component<C: 'static>(&self, entity: usize) -> Option<&C> {
+        let location = self.location(entity)?;
+        self.archetypes[location.archetype]
+            .column::<C>()?
+            .get(location.row)
+    }
+
+    /// Get (mutable) ref to component `C` of the entity.
+    pub fn component_mut<C: 'static>(&mut self, entity: usize) -> Option<&mut C> {
+        let location = self.location(entity)?;
+        self.archetypes[location.archetype]
+            .column_mut::<C>()?
+            .get_mut(location.row)
+    }
+
+    /// Create a query builder.
+    pub fn query(&self) -> ArchetypeQueryBuilder<'_> {
+        ArchetypeQueryBuilder {
+            world: self,
+            filter: ArchetypeFilter::default(),
+        }
+    }
+
+    /// Create a mutable query builder.
+    pub fn query_mut(&mut self) -> ArchetypeQueryMutBuilder<'_> {
+        ArchetypeQueryMutBuilder {
+            world: self,
+            filter: ArchetypeFilter::default(),
+        }
+    }
+
+    fn location(&self, entity: usize) -> Option<EntityLocation> {
+        self.entities.get(entity).copied().flatten()
+    }
+
+    /// Get (or create) the archetype for the sorted component `types`.
+    ///
+    /// New archetypes get empty columns of the `src` archetype, edited by `edit_columns`.
+    fn archetype_id(
+        &mut self,
+        types: Vec<TypeId>,
+        src: usize,
+        edit_columns: impl FnOnce(&mut HashMap<TypeId, Box<dyn AnyColumn>>),
+    ) -> usize {
+        if let Some(id) = self.archetype_ids.get(&types) {
+            return *id;
+        }
+
+        let mut columns = self.archetypes[src]
+            .columns
+            .iter()
+            .map(|(type_id, column)| (*type_id, column.new_empty()))
+            .collect();
+        edit_columns(&mut columns);
+        let id = self.archetypes.len();
+        self.archetypes.push(Archetype {
+            types: types.clone(),
+            columns,
+            entities: Vec::new(),
+        });
+        self.archetype_ids.insert(types, id);
+
+        id
+    }
+
+    /// Move the entity's row to the `dst` archetype.
+    ///
+    /// Columns missing in `dst` must have been removed from the row by the caller.
+    fn move_entity(&mut self, entity: usize, dst: usize) {
+        let location = self.location(entity).expect("Entity not found");
+        let mut src_archetype = mem::take(&mut self.archetypes[location.archetype]);
+        let dst_archetype = &mut self.archetypes[dst];
+        for (type_id, column) in src_archetype.columns.iter_mut() {
+            if let Some(dst_column) = dst_archetype.columns.get_mut(type_id) {
+                column.move_row(location.row, dst_column.as_mut());
+            }
+        }
+        dst_archetype.entities.push(entity);
+        let row = dst_archetype.entities.len() - 1;
+        self.archetypes[location.archetype] = src_archetype;
+
+        self.remove_row(location);
+        self.entities[entity] = Some(EntityLocation {
+            archetype: dst,
+            row,
+        });
+    }
+
+    /// Remove the entity at `location` from its archetype's entities.
+    ///
+    /// The last entity is swapped into the removed row, same as the columns.
+    fn remove_row(&mut self, location: EntityLocation) {
+        let entities = &mut self.archetypes[location.archetype].entities;
+        entities.swap_remove(location.row);
+        if let Some(swapped) = entities.get(location.row) {
+            self.entities[*swapped] = Some(location);
+        }
+    }
+}
+
+/// Builder to insert components to a spawned entity of an [`ArchetypeWorld`].
+#[derive(Debug)]
+pub struct ArchetypeEntityBuilder<'a> {
+    pub id: usize,
+    pub world: &'a mut ArchetypeWorld,
+}
+
+impl ArchetypeEntityBuilder<'_> {
+    /// Insert component `C`, replacing the existing one (if any).
+    pub fn insert<C: 'static>(&mut self, component: C) -> &mut Self {
+        self.world.insert(self.id, component);
+
+        self
+    }
+}
+
+/// Query builder for an [`ArchetypeWorld`].
+#[derive(Debug)]
+pub struct ArchetypeQueryBuilder<'a> {
+    pub world: &'a ArchetypeWorld,
+    filter: ArchetypeFilter,
+}
+
+impl<'a> ArchetypeQueryBuilder<'a> {
+    /// Filter entities to ones having component `C`.
+    pub fn with<C: 'static>(mut self) -> Self {
+        self.filter.with.insert(TypeId::of::<C>());
+        self
+    }
+
+    /// Filter out entities having component `C`.
+    pub fn without<C: 'static>(mut self) -> Self {
+        self.filter.without.insert(TypeId::of::<C>());
+        self
+    }
+
+    /// Get components of all entities matching the filters.
+    pub fn get<Q: ArchetypeQueryData>(self) -> impl Iterator<Item = (usize, Q::Item<'a>)> {
+        let filter = self.filter;
+        self.world
+            .archetypes
+            .iter()
+            .filter(move |archetype| filter.matches(archetype))
+            .filter_map(|archetype| {
+                let mut fetch = Q::init_fetch(archetype)?;
+                Some(
+                    archetype
+                        .entities
+                        .iter()
+                        .filter_map(move |entity| Some((*entity, Q::fetch(&mut fetch)?))),
+                )
+            })
+            .flatten()
+    }
+}
+
+/// Mutable query builder for an [`ArchetypeWorld`].
+#[derive(Debug)]
+pub struct ArchetypeQueryMutBuilder<'a> {
+    pub world: &'a mut ArchetypeWorld,
+    filter: ArchetypeFilter,
+}
+
+impl<'a> ArchetypeQueryMutBuilder<'a> {
+    /// Filter entities to ones having component `C`.
+    pub fn with<C: 'static>(mut self) -> Self {
+        self.filter.with.insert(TypeId::of::<C>());
+        self
+    }
+
+    /// Filter out entities having component `C`.
+    pub fn without<C: 'static>(mut self) -> Self {
+        self.filter.without.insert(TypeId::of::<C>());
+        self
+    }
+
+    /// Get (mutable) components of all entities matching the filters.
+    ///
+    /// Panics if the same component type is accessed mutably more than once, or both mutably and
+    /// immutably.
+    pub fn get_mut<Q: ArchetypeQueryDataMut>(self) -> impl Iterator<Item = (usize, Q::Item<'a>)> {
+        let filter = self.filter;
+        self.world
+            .archetypes
+            .iter_mut()
+            .filter(move |archetype| filter.matches(archetype))
+            .filter_map(|archetype| {
+                let Archetype {
+                    columns, entities, ..
+                } = archetype;
+                let mut fetch = Q::init_fetch(&mut ColumnsMut::new(columns))?;
+                Some(
+                    entities
+                        .iter()
+                        .filter_map(move |entity| Some((*entity, Q::fetch(&mut fetch)?))),
+                )
+            })
+            .flatten()
+    }
+}
+
+/// `with` / `without` filters of archetype queries.
+#[derive(Debug, Default)]
+struct ArchetypeFilter {
+    with: HashSet<TypeId>,
+    without: HashSet<TypeId>,
+}
+
+impl ArchetypeFilter {
+    fn matches(&self, archetype: &Archetype) -> bool {
+        self.with.iter().all(|type_id| archetype.has(type_id))
+            && !self.without.iter().any(|type_id| archetype.has(type_id))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct EntityLocation {
+    archetype: usize,
+    row: usize,
+}
+
+/// Entities with the exact same set of components, stored in columns.
+#[derive(Debug, Default)]
+pub struct Archetype {
+    /// Sorted component types.
+    types: Vec<TypeId>,
+    columns: HashMap<TypeId, Box<dyn AnyColumn>>,
+    /// Entity ids by row.
+    entities: Vec<usize>,
+}
+
+impl Archetype {
+    fn has(&self, type_id: &TypeId) -> bool {
+        self.columns.contains_key(type_id)
+    }
+
+    fn column<C: 'static>(&self) -> Option<&Vec<C>> {
+        let column: &dyn Any = self.columns.get(&TypeId::of::<C>())?.as_ref();
+        column.downcast_ref::<Column<C>>().map(|column| &column.0)
+    }
+
+    fn column_mut<C: 'static>(&mut self) -> Option<&mut Vec<C>> {
+        let column: &mut dyn Any = self.columns.get_mut(&TypeId::of::<C>())?.as_mut();
+        column
+            .downcast_mut::<Column<C>>()
+            .map(|column| &mut column.0)
+    }
+}
+
+/// Column of components of type `C`, indexed by archetype row.
+struct Column<C>(Vec<C>);
+
+/// Type-erased operations on a [`Column`], used when the component type is not known.
+pub trait AnyColumn: Any {
+    /// Create an empty column of the same type.
+    fn new_empty(&self) -> Box<dyn AnyColumn>;
+
+    /// Remove (and drop) the component at `row`, swapping in the last one.
+    fn swap_remove_drop(&mut self, row: usize);
+
+    /// Move the component at `row` to the end of the `to` column (of the same type).
+    fn move_row(&mut self, row: usize, to: &mut dyn AnyColumn);
+}
+
+impl Debug for dyn AnyColumn {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AnyColumn").finish_non_exhaustive()
+    }
+}
+
+impl<C: 'static> AnyColumn for Column<C> {
+    fn new_empty(&self) -> Box<dyn AnyColumn> {
+        Box::new(Column::<C>(Vec::new()))
+    }
+
+    fn swap_remove_drop(&mut self, row: usize) {
+        self.0.swap_remove(row);
+    }
+
+    fn move_row(&mut self, row: usize, to: &mut dyn AnyColumn) {
+        let to: &mut dyn Any = to;
+        let to = to
+            .downcast_mut::<Column<C>>()
+            .expect("Column type mismatch");
+        to.0.push(self.0.swap_remove(row));
+    }
+}
+
+/// Trait for data that can be fetched per entity by an archetype query.
+///
+/// Implemented for component refs `&C`, `Option<&C>` and tuples of them (up to 8 elements).
+pub trait ArchetypeQueryData {
+    /// Item yielded for each matching entity.
+    type Item<'w>;
+    /// Column iterators of an archetype.
+    type Fetch<'w>;
+
+    /// Resolve the columns of the archetype.
+    ///
+    /// Returns `None` if the archetype does not have a required component.
+    fn init_fetch(archetype: &Archetype) -> Option<Self::Fetch<'_>>;
+
+    /// Fetch the item of the next row.
+    fn fetch<'w>(fetch: &mut Self::Fetch<'w>) -> Option<Self::Item<'w>>;
+}
+
+impl<C: 'static> ArchetypeQueryData for &C {
+    type Item<'w> = &'w C;
+    type Fetch<'w> = core::slice::Iter<'w, C>;
+
+    fn init_fetch(archetype: &Archetype) -> Option<Self::Fetch<'_>> {
+        Some(archetype.column::<C>()?.iter())
+    }
+
+    fn fetch<'w>(fetch: &mut Self::Fetch<'w>) -> Option<Self::Item<'w>> {
+        fetch.next()
+    }
+}
+
+/// Optional data, fetched as `None` for entities that do not have it.
+impl<Q: ArchetypeQueryData> ArchetypeQueryData for Option<Q> {
+    type Item<'w> = Option<Q::Item<'w>>;
+    type Fetch<'w> = Option<Q::Fetch<'w>>;
+
+    fn init_fetch(archetype: &Archetype) -> Option<Self::Fetch<'_>> {
+        Some(Q::init_fetch(archetype))
+    }
+
+    fn fetch<'w>(fetch: &mut Self::Fetch<'w>) -> Option<Self::Item<'w>> {
+        Some(fetch.as_mut().and_then(Q::fetch))
+    }
+}
+
+/// Trait for data that can be fetched (mutably) per entity by an archetype query.
+///
+/// Implemented for `&mut C`, `&C`, `Option<_>` of them and tuples (up to 8 elements).
+pub trait ArchetypeQueryDataMut {
+    /// Item yielded for each matching entity.
+    type Item<'w>;
+    /// Column iterators of an archetype.
+    type Fetch<'w>;
+
+    /// Take the columns of the archetype.
+    ///
+    /// Returns `None` if the archetype does not have a required component.
+    fn init_fetch<'w>(columns: &mut ColumnsMut<'w>) -> Option<Self::Fetch<'w>>;
+
+    /// Fetch the item of the next row.
+    fn fetch<'w>(fetch: &mut Self::Fetch<'w>) -> Option<Self::Item<'w>>;
+}
+
+impl<C: 'static> ArchetypeQueryDataMut for &C {
+    type Item<'w> = &'w C;
+    type Fetch<'w> = core::slice::Iter<'w, C>;
+
+    fn init_fetch<'w>(columns: &mut ColumnsMut<'w>) -> Option<Self::Fetch<'w>> {
+        let column: &'w dyn Any = columns.take_ref(TypeId::of::<C>())?;
+        Some(column.downcast_ref::<Column<C>>()?.0.iter())
+    }
+
+    fn fetch<'w>(fetch: &mut Self::Fetch<'w>) -> Option<Self::Item<'w>> {
+        fetch.next()
+    }
+}
+
+impl<C: 'static> ArchetypeQueryDataMut for &mut C {
+    type Item<'w> = &'w mut C;
+    type Fetch<'w> = core::slice::IterMut<'w, C>;
+
+    fn init_fetch<'w>(columns: &mut ColumnsMut<'w>) -> Option<Self::Fetch<'w>> {
+        let column: &'w mut dyn Any = columns.take(TypeId::of::<C>())?;
+        Some(column.downcast_mut::<Column<C>>()?.0.iter_mut())
+    }
+
+    fn fetch<'w>(fetch: &mut Self::Fetch<'w>) -> Option<Self::Item<'w>> {
+        fetch.next()
+    }
+}
+
+/// Optional data, fetched as `None` for entities that do not have it.
+impl<Q: ArchetypeQueryDataMut> ArchetypeQueryDataMut for Option<Q> {
+    type Item<'w> = Option<Q::Item<'w>>;
+    type Fetch<'w> = Option<Q::Fetch<'w>>;
+
+    fn init_fetch<'w>(columns: &mut ColumnsMut<'w>) -> Option<Self::Fetch<'w>> {
+        Some(Q::init_fetch(columns))
+    }
+
+    fn fetch<'w>(fetch: &mut Self::Fetch<'w>) -> Option<Self::Item<'w>> {
+        Some(fetch.as_mut().and_then(Q::fetch))
+    }
+}
+
+/// Type-erased columns of an archetype, each of which can be taken mutably once, or immutably
+/// any number of times, by a query.
+pub struct ColumnsMut<'w> {
+    columns: HashMap<TypeId, Access<'w, dyn AnyColumn>>,
+}
+
+impl<'w> ColumnsMut<'w> {
+    fn new(columns: &'w mut HashMap<TypeId, Box<dyn AnyColumn>>) -> Self {
+        Self {
+            columns: columns
+                .iter_mut()
+                .map(|(type_id, column)| (*type_id, Access::Unique(column.as_mut())))
+                .collect(),
+        }
+    }
+
+    /// Take column (mutably) of the component type.
+    ///
+    /// Panics if the column was already taken.
+    fn take(&mut self, type_id: TypeId) -> Option<&'w mut dyn AnyColumn> {
+        self.columns.get_mut(&type_id).map(|access| {
+            access
+                .take_mut()
+                .expect("Component accessed more than once in a mutable query")
+        })
+    }
+
+    /// Take column (immutably) of the component type.
+    ///
+    /// Panics if the column was already taken mutably.
+    fn take_ref(&mut self, type_id: TypeId) -> Option<&'w dyn AnyColumn> {
+        self.columns.get_mut(&type_id).map(|access| {
+            access
+                .take_ref()
+                .expect("Component accessed more than once in a mutable query")
+        })
+    }
+}
+
+macro_rules! impl_archetype_query_data_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: ArchetypeQueryData),+> ArchetypeQueryData for ($($name,)+) {
+            type Item<'w> = ($($name::Item<'w>,)+);
+            type Fetch<'w> = ($($name::Fetch<'w>,)+);
+
+            fn init_fetch(archetype: &Archetype) -> Option<Self::Fetch<'_>> {
+                Some(($($name::init_fetch(archetype)?,)+))
+            }
+
+            #[allow(non_snake_case)]
+            fn fetch<'w>(fetch: &mut Self::Fetch<'w>) -> Option<Self::Item<'w>> {
+                let ($($name,)+) = fetch;
+                Some(($($name::fetch($name)?,)+))
+            }
+        }
+
+        impl<$($name: ArchetypeQueryDataMut),+> ArchetypeQueryDataMut for ($($name,)+) {
+            type Item<'w> = ($($name::Item<'w>,)+);
+            type Fetch<'w> = ($($name::Fetch<'w>,)+);
+
+            fn init_fetch<'w>(columns: &mut ColumnsMut<'w>) -> Option<Self::Fetch<'w>> {
+                Some(($($name::init_fetch(columns)?,)+))
+            }
+
+            #[allow(non_snake_case)]
+            fn fetch<'w>(fetch: &mut Self::Fetch<'w>) -> Option<Self::Item<'w>> {
+                let ($($name,)+) = fetch;
+                Some(($($name::fetch($name)?,)+))
+            }
+        }
+    };
+}
+
+impl_archetype_query_data_tuple!(Q0);
+impl_archetype_query_data_tuple!(Q0, Q1);
+impl_archetype_query_data_tuple!(Q0, Q1, Q2);
+impl_archetype_query_data_tuple!(Q0, Q1, Q2, Q3);
+impl_archetype_query_data_tuple!(Q0, Q1, Q2, Q3, Q4);
+impl_archetype_query_data_tuple!(Q0, Q1, Q2, Q3, Q4, Q5);
+impl_archetype_query_data_tuple!(Q0, Q1, Q2, Q3, Q4, Q5, Q6);
+impl_archetype_query_data_tuple!(Q0, Q1, Q2, Q3, Q4, Q5, Q6, Q7);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn entities_move_between_archetypes() {
+        #[derive(Debug, PartialEq)]
+        struct Player(&'static str);
+        #[derive(Debug, PartialEq)]
+        struct Age(u8);
+        #[derive(Debug, PartialEq)]
+        struct Dead;
+
+        let mut world = ArchetypeWorld::new();
+        let mike = world.spawn().insert(Player("Mike")).insert(Age(30)).id;
+        let hannah = world.spawn().insert(Player("Hannah")).insert(Age(25)).id;
+        let sam = world.spawn().insert(Player("Sam")).id;
+        // Empty, (Player), (Player, Age)...
+        assert_eq!(world.archetype_count(), 3);
+
+        // Insert existing replaces in place...
+        assert_eq!(world.insert(mike, Age(31)), Some(Age(30)));
+        // Insert new moves to (Player, Age, Dead)...
+        assert_eq!(world.insert(mike, Dead), None);
+        assert_eq!(world.archetype_count(), 4);
+        assert_eq!(world.component::<Age>(mike), Some(&Age(31)));
+        assert_eq!(world.component::<Player>(hannah), Some(&Player("Hannah")));
+
+        // Remove moves back to (Player, Age)...
+        assert_eq!(world.remove_component::<Dead>(mike), Some(Dead));
+        assert_eq!(world.remove_component::<Dead>(mike), None);
+        assert_eq!(world.archetype_count(), 4);
+
+        // Despawn swaps rows and re-uses ids...
+        assert!(world.despawn(mike));
+        assert!(!world.despawn(mike));
+        assert_eq!(world.component::<Player>(hannah), Some(&Player("Hannah")));
+        assert_eq!(world.component::<Age>(hannah), Some(&Age(25)));
+        assert_eq!(world.len(), 2);
+        let rahul = world.spawn().insert(Player("Rahul")).id;
+        assert_eq!(rahul, mike);
+        assert_eq!(world.component::<Player>(sam), Some(&Player("Sam")));
+    }
+
+    #[test]
+    fn queries_visit_matching_archetypes() {
+        struct Player(&'static str);
+        struct Age(u8);
+        struct Dead;
+        struct NeverInserted;
+
+        let mut world = ArchetypeWorld::new();
+        world
+            .spawn()
+            .insert(Player("Mike"))
+            .insert(Age(30))
+            .insert(Dead);
+        world.spawn().insert(Player("Hannah")).insert(Age(25));
+        world.spawn().insert(Player("Sam"));
+
+        let results = world
+            .query()
+            .without::<Dead>()
+            .get::<(&Player, Option<&Age>)>()
+            .map(|(_, (player, age))| (player.0, age.map(|age| age.0)))
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![("Sam", None), ("Hannah", Some(25))]);
+        assert_eq!(
+            world
+                .query()
+                .with::<NeverInserted>()
+                .get::<&Player>()
+                .count(),
+            0
+        );
+
+        world
+            .query_mut()
+            .with::<Player>()
+            .get_mut::<(&mut Age, &Player)>()
+            .for_each(|(_, (age, _))| age.0 += 1);
+        let mut ages = world
+            .query()
+            .get::<&Age>()
+            .map(|(_, age)| age.0)
+            .collect::<Vec<_>>();
+        ages.sort();
+        assert_eq!(ages, vec![26, 31]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Component accessed more than once")]
+    fn get_mut_conflicting_access_panics() {
+        struct Age(#[allow(dead_code)] u8);
+
+        let mut world = ArchetypeWorld::new();
+        world.spawn().insert(Age(30));
+        let _ = world.query_mut().get_mut::<(&mut Age, &Age)>().count();
+    }
+
+    #[test]
+    fn get_mut_repeated_shared_access() {
+        struct Age(u8);
+
+        let mut world = ArchetypeWorld::new();
+        world.spawn().insert(Age(30));
+        let results = world
+            .query_mut()
+            .get_mut::<(&Age, &Age)>()
+            .map(|(_, (a, b))| (a.0, b.0))
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![(30, 30)]);
+    }
+}
@@ -0,0 +1,183 @@
+use core::any::Any;
+
+use crate::{
+    entity_builder::EntityBuilderMethods,
+    type_map::ThreadSafe,
+    world::{World, WorldValue},
+};
+
+/// Group of components inserted into an entity in one go, via
+/// [`WorldMethods::spawn_bundle`](crate::prelude::WorldMethods::spawn_bundle) or
+/// [`EntityBuilderMethods::insert_bundle`].
+///
+/// Implemented for tuples of components (up to 12 elements), and derivable for structs
+/// (feature `derive`) inserting each of their fields as a component.
+///
+/// `V` is the [`WorldValue`] of the world, ie. components of bundles spawned in a thread-safe
+/// world need to be `Send + Sync`.
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// #[derive(Debug, PartialEq)]
+/// struct Player(&'static str);
+/// #[derive(Debug, PartialEq)]
+/// struct Age(u8);
+/// struct Footballer;
+///
+/// let mut world = World::new();
+/// let mike = world.spawn_bundle((Player("Mike"), Age(30), Footballer)).id;
+/// let hannah = world
+///     .spawn()
+///     .insert(Player("Hannah"))
+///     .insert_bundle((Age(25), Footballer))
+///     .id;
+///
+/// assert_eq!(world.component::<Age>(mike), Some(&Age(30)));
+/// assert_eq!(world.component::<Age>(hannah), Some(&Age(25)));
+/// let mut query = world.query();
+/// assert_eq!(query.with::<Footballer>().get::<&Player>().unwrap().count(), 2);
+/// ```
+pub trait Bundle<V: ?Sized + WorldValue = dyn Any>: 'static {
+    /// Number of components in the bundle.
+    const COUNT: usize;
+
+    /// Insert the components into the entity, via [`EntityBuilderMethods::insert`].
+    fn insert_into<'a, B: ?Sized + EntityBuilderMethods<'a, Erased = V>>(self, builder: &mut B);
+
+    /// Reserve capacity for at least `additional` more of each component in the world's
+    /// tables, via [`EntityBuilderMethods::reserve_table`].
+    fn reserve_tables<'a, B: ?Sized + EntityBuilderMethods<'a, Erased = V>>(
+        world: &mut World<B::Key, B::EntityStorage>,
+        additional: usize,
+    );
+}
+
+macro_rules! impl_bundle_tuple {
+    ($($name:ident),+) => {
+        impl<V: ?Sized + WorldValue, $($name: 'static),+> Bundle<V> for ($($name,)+)
+        where
+            $(ThreadSafe<V, $name>: Send + Sync,)+
+        {
+            // One per component type...
+            const COUNT: usize = 0 $(+ { let _ = stringify!($name); 1 })+;
+
+            #[allow(non_snake_case)]
+            fn insert_into<'a, B: ?Sized + EntityBuilderMethods<'a, Erased = V>>(self, builder: &mut B) {
+                let ($($name,)+) = self;
+                $(builder.insert($name);)+
+            }
+
+            fn reserve_tables<'a, B: ?Sized + EntityBuilderMethods<'a, Erased = V>>(
+                world: &mut World<B::Key, B::EntityStorage>,
+                additional: usize,
+            ) {
+                $(B::reserve_table::<$name>(world, additional);)+
+            }
+        }
+    };
+}
+
+impl_bundle_tuple!(C0);
+impl_bundle_tuple!(C0, C1);
+impl_bundle_tuple!(C0, C1, C2);
+impl_bundle_tuple!(C0, C1, C2, C3);
+impl_bundle_tuple!(C0, C1, C2, C3, C4);
+impl_bundle_tuple!(C0, C1, C2, C3, C4, C5);
+impl_bundle_tuple!(C0, C1, C2, C3, C4, C5, C6);
+impl_bundle_tuple!(C0, C1, C2, C3, C4, C5, C6, C7);
+impl_bundle_tuple!(C0, C1, C2, C3, C4, C5, C6, C7, C8);
+impl_bundle_tuple!(C0, C1, C2, C3, C4, C5, C6, C7, C8, C9);
+impl_bundle_tuple!(C0, C1, C2, C3, C4, C5, C6, C7, C8, C9, C10);
+impl_bundle_tuple!(C0, C1, C2, C3, C4, C5, C6, C7, C8, C9, C10, C11);
+
+#[cfg(all(test, feature = "slab"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn bundles() {
+        #[derive(Debug, PartialEq)]
+        struct Player(&'static str);
+        #[derive(Debug, PartialEq)]
+        struct Age(u8);
+        #[derive(Debug, PartialEq)]
+        struct Footballer;
+
+        let mut world = World::new();
+        let mike = world.spawn_bundle((Player("Mike"), Age(30), Footballer)).id;
+        assert_eq!(world.component::<Player>(mike), Some(&Player("Mike")));
+        assert_eq!(world.component::<Age>(mike), Some(&Age(30)));
+        assert_eq!(world.component::<Footballer>(mike), Some(&Footballer));
+        assert_eq!(<(Player, Age, Footballer) as Bundle>::COUNT, 3);
+
+        // Replaces existing components...
+        let hannah = world
+            .spawn()
+            .insert(Player("Hannah"))
+            .insert(Age(20))
+            .insert_bundle((Age(25), Footballer))
+            .id;
+        assert_eq!(world.component::<Player>(hannah), Some(&Player("Hannah")));
+        assert_eq!(world.component::<Age>(hannah), Some(&Age(25)));
+        let mut query = world.query();
+        assert_eq!(query.get::<&Age>().unwrap().count(), 2);
+
+        // Thread-safe world...
+        let mut world = SyncWorld::new();
+        let entity = world.spawn_bundle((Player("Mike"), Age(30))).id;
+        assert_eq!(world.component::<Age>(entity), Some(&Age(30)));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_bundles() {
+        #[derive(Debug, PartialEq)]
+        struct Player(&'static str);
+        #[derive(Debug, PartialEq)]
+        struct Age(u8);
+        #[derive(Debug, PartialEq)]
+        struct Position<T>(T);
+
+        #[derive(Bundle)]
+        struct PlayerBundle {
+            player: Player,
+            age: Age,
+        }
+
+        #[derive(Bundle)]
+        struct Spawned<T: 'static>(Position<T>, Age);
+
+        #[derive(Bundle)]
+        struct Empty;
+
+        assert_eq!(<PlayerBundle as Bundle>::COUNT, 2);
+        assert_eq!(<Spawned<f32> as Bundle>::COUNT, 2);
+        assert_eq!(<Empty as Bundle>::COUNT, 0);
+
+        let mut world = World::new();
+        let mike = world
+            .spawn_bundle(PlayerBundle {
+                player: Player("Mike"),
+                age: Age(30),
+            })
+            .insert_bundle(Spawned(Position(1.0_f32), Age(31)))
+            .insert_bundle(Empty)
+            .id;
+        assert_eq!(world.component::<Player>(mike), Some(&Player("Mike")));
+        assert_eq!(world.component::<Age>(mike), Some(&Age(31)));
+        assert_eq!(world.component::<Position<f32>>(mike), Some(&Position(1.0)));
+        let spawned = world.spawn_batch((0..4).map(|i| Spawned(Position(i), Age(i as u8))));
+        assert_eq!(world.component::<Age>(spawned[3]), Some(&Age(3)));
+
+        let mut world = SyncWorld::new();
+        let hannah = world
+            .spawn_bundle(PlayerBundle {
+                player: Player("Hannah"),
+                age: Age(25),
+            })
+            .id;
+        assert_eq!(world.component::<Age>(hannah), Some(&Age(25)));
+    }
+}
@@ -0,0 +1,283 @@
+use core::{any::Any, fmt::Debug};
+
+use crate::{
+    entity_builder::EntityBuilderMethods,
+    storage::Storage,
+    type_map::{AnyValue, ThreadSafe},
+    world::WorldMethods,
+};
+
+/// Deferred component insertion, the component is boxed as the world's values (along with the
+/// fn inserting it) so the buffer is `Send + Sync` for thread-safe worlds.
+struct InsertCommand<W: WorldMethods> {
+    insert: fn(&mut W, W::Key, Box<dyn Any>),
+    component: Box<W::Erased>,
+}
+
+impl<W: WorldMethods> InsertCommand<W> {
+    fn new<C: 'static>(component: C) -> Self
+    where
+        ThreadSafe<W::Erased, C>: Send + Sync,
+    {
+        Self {
+            insert: |world, entity, component| {
+                W::AssocEntityBuilder::create(entity, world.world_mut())
+                    .insert(*component.downcast::<C>().unwrap());
+            },
+            component: W::Erased::erase(component),
+        }
+    }
+
+    fn apply(self, world: &mut W, entity: W::Key) {
+        (self.insert)(world, entity, self.component.into_any());
+    }
+}
+
+/// Recorded structural change on a world.
+enum Command<W: WorldMethods> {
+    /// Spawn an entity and insert components into it.
+    Spawn(Vec<InsertCommand<W>>),
+    /// Insert a component into the entity.
+    Insert(W::Key, InsertCommand<W>),
+    /// Remove a component from the entity.
+    Remove(W::Key, fn(&mut W, W::Key)),
+    /// Despawn the entity.
+    Despawn(W::Key),
+    /// Any other change, the boxed `FnOnce(&mut W)` along with the fn calling it.
+    Apply(fn(&mut W, Box<dyn Any>), Box<W::Erased>),
+}
+
+/// Buffer of structural changes (spawn, insert, remove, despawn) to apply to a world later.
+///
+/// Allows queueing changes while the world is borrowed, eg. when iterating query results.
+/// Commands are applied in the order they were recorded.
+///
+/// __Note: Commands on entities that do not exist (anymore) when applied are skipped.__
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// struct Health(u8);
+/// struct Dead;
+///
+/// let mut world = World::new();
+/// world.spawn().insert(Health(0));
+/// world.spawn().insert(Health(10));
+///
+/// let mut commands = Commands::new();
+/// let mut query = world.query();
+/// for (entity, health) in query.get::<&Health>().unwrap() {
+///     if health.0 == 0 {
+///         commands.insert(entity, Dead);
+///     }
+/// }
+/// commands.spawn().insert(Health(20));
+/// commands.apply(&mut world);
+///
+/// let mut query = world.query();
+/// assert_eq!(query.with::<Dead>().get::<&Health>().unwrap().count(), 1);
+/// let mut query = world.query();
+/// assert_eq!(query.get::<&Health>().unwrap().count(), 3);
+/// ```
+pub struct Commands<W: WorldMethods> {
+    queue: Vec<Command<W>>,
+}
+
+impl<W: WorldMethods + 'static> Commands<W> {
+    /// Create a new empty [`Commands`] buffer.
+    pub fn new() -> Self {
+        Self { queue: Vec::new() }
+    }
+
+    /// Number of recorded commands.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// If no commands are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Spawn an entity, with the components inserted via the returned [`SpawnCommands`].
+    pub fn spawn(&mut self) -> SpawnCommands<'_, W> {
+        self.queue.push(Command::Spawn(Vec::new()));
+        let Some(Command::Spawn(inserts)) = self.queue.last_mut() else {
+            unreachable!()
+        };
+
+        SpawnCommands { inserts }
+    }
+
+    /// Insert component `C` into the entity, replacing the existing one (if any).
+    pub fn insert<C: 'static>(&mut self, entity: W::Key, component: C) -> &mut Self
+    where
+        ThreadSafe<W::Erased, C>: Send + Sync,
+    {
+        self.queue
+            .push(Command::Insert(entity, InsertCommand::new(component)));
+
+        self
+    }
+
+    /// Remove component `C` from the entity.
+    pub fn remove<C: 'static>(&mut self, entity: W::Key) -> &mut Self {
+        self.queue.push(Command::Remove(entity, |world, entity| {
+            world.remove_component::<C>(entity);
+        }));
+
+        self
+    }
+
+    /// Despawn the entity.
+    pub fn despawn(&mut self, entity: W::Key) -> &mut Self {
+        self.queue.push(Command::Despawn(entity));
+
+        self
+    }
+
+    /// Record a custom change on the world.
+    pub fn add<F: FnOnce(&mut W) + 'static>(&mut self, command: F) -> &mut Self
+    where
+        ThreadSafe<W::Erased, F>: Send + Sync,
+    {
+        self.queue.push(Command::Apply(
+            |world, command| command.downcast::<F>().unwrap()(world),
+            W::Erased::erase(command),
+        ));
+
+        self
+    }
+
+    /// Apply the recorded commands to the world in order, leaving the buffer empty.
+    pub fn apply(&mut self, world: &mut W) {
+        for command in self.queue.drain(..) {
+            match command {
+                Command::Spawn(inserts) => {
+                    let entity = world.spawn().id();
+                    inserts
+                        .into_iter()
+                        .for_each(|insert| insert.apply(world, entity));
+                }
+                Command::Insert(entity, insert) => {
+                    if world.world().entities.0.has(entity) {
+                        insert.apply(world, entity);
+                    }
+                }
+                Command::Remove(entity, remove) => remove(world, entity),
+                Command::Despawn(entity) => {
+                    world.despawn(entity);
+                }
+                Command::Apply(apply, command) => apply(world, command.into_any()),
+            }
+        }
+    }
+}
+
+impl<W: WorldMethods + 'static> Default for Commands<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: WorldMethods> Debug for Commands<W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Commands")
+            .field("len", &self.queue.len())
+            .finish()
+    }
+}
+
+/// Components to insert into an entity spawned by [`Commands::spawn`].
+pub struct SpawnCommands<'a, W: WorldMethods> {
+    inserts: &'a mut Vec<InsertCommand<W>>,
+}
+
+impl<W: WorldMethods + 'static> SpawnCommands<'_, W> {
+    /// Insert component `C` into the spawned entity.
+    pub fn insert<C: 'static>(&mut self, component: C) -> &mut Self
+    where
+        ThreadSafe<W::Erased, C>: Send + Sync,
+    {
+        self.inserts.push(InsertCommand::new(component));
+
+        self
+    }
+}
+
+#[cfg(all(test, feature = "slab"))]
+mod tests {
+    use slab::Slab;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn commands_apply_in_order() {
+        #[derive(Debug, PartialEq)]
+        struct Player(&'static str);
+        #[derive(Debug, PartialEq)]
+        struct Age(u8);
+
+        let mut world = World::new();
+        let mike = world.spawn().insert(Player("Mike")).insert(Age(30)).id;
+        let hannah = world.spawn().insert(Player("Hannah")).id;
+
+        let mut commands = Commands::new();
+        commands
+            .insert(hannah, Age(25))
+            .remove::<Age>(mike)
+            .despawn(hannah)
+            // Skipped as the entity is despawned by then...
+            .insert(hannah, Player("Ghost"));
+        commands.spawn().insert(Player("Sam")).insert(Age(20));
+        assert_eq!(commands.len(), 5);
+        assert!(world.component_mut::<Age>(mike).is_some());
+
+        commands.apply(&mut world);
+        assert!(commands.is_empty());
+        assert_eq!(world.component_mut::<Age>(mike), None);
+        let mut query = world.query();
+        let results = query
+            .get::<(&Player, Option<&Age>)>()
+            .unwrap()
+            .map(|(_, (player, age))| (player.0, age.map(|age| age.0)))
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![("Mike", None), ("Sam", Some(20))]);
+    }
+
+    #[test]
+    fn commands_in_systems_apply_after_stage() {
+        struct Spawner;
+        struct Spawned;
+
+        fn spawn_from_spawners(
+            spawners: Query<usize, &Spawner>,
+            commands: &mut Commands<World<usize, Slab<TypeMap>>>,
+        ) {
+            for _ in spawners {
+                commands.spawn().insert(Spawned);
+            }
+        }
+
+        fn count_spawned(spawned: Query<usize, &Spawned>, mut count: ResMut<usize>) {
+            *count = spawned.count();
+        }
+
+        let mut world = World::new();
+        world.spawn().insert(Spawner);
+        world.spawn().insert(Spawner);
+        world.insert_resource(0usize);
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(Stage::Update, spawn_from_spawners)
+            // Runs in the same stage, so does not see the spawned entities yet...
+            .add_system(Stage::Update, count_spawned)
+            .add_system(Stage::PostUpdate, count_spawned);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<usize>(), Some(&2));
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<usize>(), Some(&4));
+    }
+}
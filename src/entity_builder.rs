@@ -1,18 +1,21 @@
-use core::marker::PhantomData;
+use core::any::TypeId;
 
 use crate::{
+    bundle::Bundle,
     storage::{Identifier, Storage},
-    type_map::TypeMap,
-    world::{ComponentId, Table, World},
+    type_map::{ThreadSafe, TypeMap},
+    world::{ComponentId, EntityTypeMap, TableStorage, World, WorldValue},
 };
 
 #[derive(Debug)]
-pub struct EntityBuilder<'a, I: Identifier, E: Storage<Key = I, Value = TypeMap>> {
+pub struct EntityBuilder<'a, I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>> {
     pub id: I,
     pub world: &'a mut World<I, E>,
 }
 
-impl<'a, I: Identifier, E: Storage<Key = I, Value = TypeMap>> EntityBuilder<'a, I, E> {
+impl<'a, I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>>
+    EntityBuilder<'a, I, E>
+{
     pub fn new(id: I, world: &'a mut World<I, E>) -> Self {
         Self { id, world }
     }
@@ -20,8 +23,12 @@ impl<'a, I: Identifier, E: Storage<Key = I, Value = TypeMap>> EntityBuilder<'a,
 
 pub trait EntityBuilderMethods<'a> {
     type Key: Identifier + 'static;
-    type EntityStorage: Storage<Key = Self::Key, Value = TypeMap>;
-    type ComponentStorage<T: 'static>: Storage<Key = Self::Key, Value = T> + 'static;
+    type Erased: ?Sized + WorldValue;
+    type EntityStorage: Storage<Key = Self::Key, Value = TypeMap<Self::Erased>>;
+    type ComponentStorage<T: 'static>: Storage<Key = Self::Key, Value = T>
+        + TableStorage<Self::Erased>
+    where
+        ThreadSafe<Self::Erased, T>: Send + Sync;
 
     fn create(id: Self::Key, world: &'a mut World<Self::Key, Self::EntityStorage>) -> Self;
 
@@ -29,39 +36,92 @@ pub trait EntityBuilderMethods<'a> {
 
     fn world(&mut self) -> &mut World<Self::Key, Self::EntityStorage>;
 
-    fn insert<C: 'static>(&mut self, component: C) -> &mut Self {
+    /// Insert component `C`, replacing the existing one in place (if any).
+    fn insert<C: 'static>(&mut self, component: C) -> &mut Self
+    where
+        ThreadSafe<Self::Erased, C>: Send + Sync,
+    {
+        self.replace(component);
+
+        self
+    }
+
+    /// Reserve capacity for at least `additional` more components `C` in the world's table.
+    fn reserve_table<C: 'static>(
+        world: &mut World<Self::Key, Self::EntityStorage>,
+        additional: usize,
+    ) where
+        ThreadSafe<Self::Erased, C>: Send + Sync,
+    {
+        world
+            .all_tables
+            .reserve::<C, Self::ComponentStorage<C>>(additional);
+    }
+
+    /// Insert the components of the bundle, replacing the existing ones (if any),
+    /// see [`Bundle`].
+    fn insert_bundle<B: Bundle<Self::Erased>>(&mut self, bundle: B) -> &mut Self {
+        let id = self.id();
+        if let Some(entity) = self.world().entities.0.get_mut(id) {
+            entity.reserve(B::COUNT);
+        }
+        bundle.insert_into(self);
+
+        self
+    }
+
+    /// Insert component `C` only if the entity does not have it already.
+    ///
+    /// Returns the passed component back as `Err` if it was not inserted.
+    fn try_insert<C: 'static>(&mut self, component: C) -> Result<&mut Self, C>
+    where
+        ThreadSafe<Self::Erased, C>: Send + Sync,
+    {
+        let id = self.id();
+        let has_component = self
+            .world()
+            .entities
+            .0
+            .get(id)
+            .is_some_and(|entity| entity.has::<ComponentId<C, Self::Key>>());
+        if has_component {
+            return Err(component);
+        }
+
+        Ok(self.insert(component))
+    }
+
+    /// Insert component `C`, returning the previous value if the entity already had one.
+    fn replace<C: 'static>(&mut self, component: C) -> Option<C>
+    where
+        ThreadSafe<Self::Erased, C>: Send + Sync,
+    {
         let id = self.id();
         let world = self.world();
-        let table = if let Some(table) = world.all_tables.0.get_mut::<Table<
-            C,
-            Self::Key,
-            Self::ComponentStorage<C>,
-        >>() {
-            table
-        } else {
-            world
-                .all_tables
-                .0
-                .insert::<Table<C, Self::Key, Self::ComponentStorage<C>>>(Table::new());
-            world
-                .all_tables
-                .0
-                .get_mut::<Table<C, Self::Key, Self::ComponentStorage<C>>>()
-                .unwrap()
-        };
-
-        let comp_id = table.storage.insert(component);
         let entity = world
             .entities
             .0
             .get_mut(id)
             .unwrap_or_else(|| panic!("Entity not found for id: {id}"));
+        let change_tick = world.ticks.change_tick;
+        let table = world
+            .all_tables
+            .get_or_insert::<C, Self::ComponentStorage<C>>();
 
-        entity.insert::<ComponentId<C, Self::Key>>(ComponentId {
-            id: comp_id,
-            _phantom_data: PhantomData,
-        });
+        // Replace in place to not orphan the previous value in the table...
+        if let Some(existing) = entity
+            .get::<ComponentId<C, Self::Key>>()
+            .and_then(|comp_id| table.get_mut(comp_id.id, change_tick))
+        {
+            return Some(core::mem::replace(existing, component));
+        }
 
-        self
+        let comp_id = table.insert(component, change_tick);
+        entity.insert_boxed(
+            TypeId::of::<ComponentId<C, Self::Key>>(),
+            Self::ComponentStorage::<C>::erase_component_id(comp_id),
+        );
+
+        None
     }
 }
@@ -0,0 +1,185 @@
+use core::{any::TypeId, marker::PhantomData};
+use std::collections::HashMap;
+
+use crate::{
+    type_map::{AnyValue, ThreadSafe},
+    world::{WorldMethods, WorldValue},
+};
+
+/// Double buffered queue of events of type `E`.
+///
+/// Events live for two [`WorldMethods::update_events`] calls, giving readers a full update
+/// cycle to read them.
+#[derive(Debug)]
+pub(crate) struct Events<E> {
+    /// Events sent before the last update.
+    previous: Vec<E>,
+    /// Events sent since the last update.
+    current: Vec<E>,
+    /// Count of all events ever sent.
+    event_count: usize,
+}
+
+impl<E> Default for Events<E> {
+    fn default() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+            event_count: 0,
+        }
+    }
+}
+
+impl<E> Events<E> {
+    fn send(&mut self, event: E) {
+        self.current.push(event);
+        self.event_count += 1;
+    }
+
+    /// Swap the buffers, dropping the events of the previous update.
+    fn update(&mut self) {
+        self.previous = core::mem::take(&mut self.current);
+    }
+
+    /// Events still buffered that were sent after the first `event_count` events.
+    fn since(&self, event_count: usize) -> impl Iterator<Item = &E> {
+        let buffered_start = self.event_count - self.current.len() - self.previous.len();
+        self.previous
+            .iter()
+            .chain(self.current.iter())
+            .skip(event_count.saturating_sub(buffered_start))
+    }
+}
+
+/// [`Events`] of all types, boxed and keyed by event type.
+#[derive(Debug)]
+pub(crate) struct AllEvents<V: ?Sized + WorldValue>(HashMap<TypeId, Events<Box<V>>>);
+
+impl<V: ?Sized + WorldValue> Default for AllEvents<V> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<V: ?Sized + WorldValue> AllEvents<V> {
+    pub(crate) fn send<E: 'static>(&mut self, event: E)
+    where
+        ThreadSafe<V, E>: Send + Sync,
+    {
+        self.0
+            .entry(TypeId::of::<E>())
+            .or_default()
+            .send(V::erase(event));
+    }
+
+    pub(crate) fn get<E: 'static>(&self) -> Option<&Events<Box<V>>> {
+        self.0.get(&TypeId::of::<E>())
+    }
+
+    pub(crate) fn update(&mut self) {
+        self.0.values_mut().for_each(Events::update);
+    }
+}
+
+/// Reader of events of type `E` sent via [`WorldMethods::send_event`].
+///
+/// Keeps a cursor, so each event is read once per reader. Events are kept for two
+/// [`WorldMethods::update_events`] calls, so read at least once in between to not miss any.
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// #[derive(Debug, PartialEq)]
+/// struct Collision(u32, u32);
+///
+/// let mut world = World::new();
+/// let mut reader = EventReader::<Collision>::new();
+/// world.send_event(Collision(1, 2));
+/// assert_eq!(reader.read(&world).collect::<Vec<_>>(), vec![&Collision(1, 2)]);
+/// // Already read...
+/// assert_eq!(reader.read(&world).count(), 0);
+///
+/// world.send_event(Collision(2, 3));
+/// world.update_events();
+/// world.update_events();
+/// // Dropped after two updates...
+/// assert_eq!(reader.read(&world).count(), 0);
+/// ```
+#[derive(Debug)]
+pub struct EventReader<E> {
+    /// Count of events sent when last read.
+    last_event_count: usize,
+    _phantom_data: PhantomData<fn() -> E>,
+}
+
+impl<E> Default for EventReader<E> {
+    fn default() -> Self {
+        Self {
+            last_event_count: 0,
+            _phantom_data: PhantomData,
+        }
+    }
+}
+
+impl<E: 'static> EventReader<E> {
+    /// Create a new [`EventReader`], reading all events still buffered on first read.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the events sent since the last read.
+    pub fn read<'w, W: WorldMethods>(
+        &mut self,
+        world: &'w W,
+    ) -> impl Iterator<Item = &'w E> + use<'w, E, W> {
+        let events = world.world().events.get::<E>();
+        let last_event_count = self.last_event_count;
+        if let Some(events) = events {
+            self.last_event_count = events.event_count;
+        }
+
+        events
+            .into_iter()
+            .flat_map(move |events| events.since(last_event_count))
+            .map(|event| event.as_any().downcast_ref::<E>().unwrap())
+    }
+}
+
+#[cfg(all(test, feature = "slab"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn events_are_double_buffered() {
+        #[derive(Debug, PartialEq)]
+        struct Jump(u8);
+        #[derive(Debug, PartialEq)]
+        struct Land;
+
+        let mut world = World::new();
+        let mut early = EventReader::<Jump>::new();
+        let mut late = EventReader::<Jump>::new();
+        assert_eq!(early.read(&world).count(), 0);
+
+        world.send_event(Jump(1));
+        world.send_event(Land);
+        assert_eq!(early.read(&world).collect::<Vec<_>>(), vec![&Jump(1)]);
+
+        world.update_events();
+        world.send_event(Jump(2));
+        assert_eq!(early.read(&world).collect::<Vec<_>>(), vec![&Jump(2)]);
+
+        // Events of the previous update are still readable...
+        world.update_events();
+        world.send_event(Jump(3));
+        assert_eq!(
+            late.read(&world).collect::<Vec<_>>(),
+            vec![&Jump(2), &Jump(3)]
+        );
+        assert_eq!(early.read(&world).collect::<Vec<_>>(), vec![&Jump(3)]);
+
+        // Land events were updated too...
+        assert_eq!(EventReader::<Land>::new().read(&world).count(), 0);
+    }
+}
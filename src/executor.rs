@@ -0,0 +1,324 @@
+use core::{fmt::Debug, ops::Range};
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::{
+    commands::Commands,
+    schedule::{Executor, IntoBoxedSystem, Schedule, Stage},
+    system::{System, SystemAccess, SystemWorld},
+    world::WorldMethods,
+};
+
+/// [`Schedule`] running the systems of a thread-safe world (eg.
+/// [`SyncWorld`](crate::prelude::SyncWorld)) in parallel, see [`ParallelExecutor`].
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// struct Position(f32);
+/// struct Velocity(f32);
+/// struct Health(u8);
+///
+/// fn movement(query: Query<usize, (&mut Position, &Velocity)>) {
+///     for (_, (position, velocity)) in query {
+///         position.0 += velocity.0;
+///     }
+/// }
+///
+/// fn regen(query: Query<usize, &mut Health>) {
+///     for (_, health) in query {
+///         health.0 += 1;
+///     }
+/// }
+///
+/// fn fall(query: Query<usize, &mut Velocity>) {
+///     for (_, velocity) in query {
+///         velocity.0 -= 1.0;
+///     }
+/// }
+///
+/// let mut world = SyncWorld::new();
+/// world.spawn().insert(Position(0.0)).insert(Velocity(1.0)).insert(Health(0));
+///
+/// let mut schedule = ParallelSchedule::default();
+/// schedule
+///     .add_system(Stage::Update, movement)
+///     .add_system(Stage::Update, regen)
+///     .add_system(Stage::Update, fall);
+/// // `movement` and `regen` run in parallel, `fall` conflicts with `movement` on `Velocity`.
+/// assert_eq!(schedule.batches(Stage::Update), vec![0..2, 2..3]);
+///
+/// schedule.run(&mut world);
+/// let mut query = world.query();
+/// let (_, (position, velocity)) = query.get::<(&Position, &Velocity)>().unwrap().next().unwrap();
+/// assert_eq!((position.0, velocity.0), (1.0, 0.0));
+/// ```
+pub type ParallelSchedule<W> = Schedule<W, ParallelExecutor>;
+
+impl<W: WorldMethods + 'static> ParallelSchedule<W>
+where
+    for<'w> SystemWorld<'w, W>: Send,
+{
+    /// Create a new empty [`ParallelSchedule`], with `threads` number of threads
+    /// (`0` for a thread per CPU).
+    ///
+    /// Panics if the thread pool fails to build.
+    pub fn with_threads(threads: usize) -> Self {
+        Self::with_executor(ParallelExecutor::with_threads(threads))
+    }
+
+    /// Ranges of the systems (by the order they were added) of the stage, which run in
+    /// parallel together.
+    pub fn batches(&mut self, stage: Stage) -> Vec<Range<usize>> {
+        ParallelExecutor::batch(self.systems_mut(stage))
+    }
+}
+
+/// [`Executor`] running systems in parallel on a rayon thread pool.
+///
+/// Systems of a stage are split into batches in the order they were added, a system joining the
+/// current batch unless its access conflicts with any system in it (see [`ParallelSystem`]).
+/// Systems of a batch run in parallel, and batches run one after another. Systems with exclusive
+/// access to the world (taking `&mut W`, or not being parallel systems) always run alone. Hence
+/// the results are the same as running the systems in order via a [`Schedule`].
+///
+/// [`ParallelSystem`]: crate::prelude::ParallelSystem
+///
+/// [`Commands`] recorded by systems are applied at the end of each stage, in the order the
+/// systems were added.
+pub struct ParallelExecutor {
+    pool: ThreadPool,
+}
+
+impl ParallelExecutor {
+    /// Create a new [`ParallelExecutor`], with a thread per CPU.
+    pub fn new() -> Self {
+        Self::with_threads(0)
+    }
+
+    /// Create a new [`ParallelExecutor`], with `threads` number of threads
+    /// (`0` for a thread per CPU).
+    ///
+    /// Panics if the thread pool fails to build.
+    pub fn with_threads(threads: usize) -> Self {
+        Self {
+            pool: ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .expect("Failed to build thread pool"),
+        }
+    }
+
+    /// Access of the system, exclusive unless it is a parallel system.
+    fn access<W: WorldMethods>(system: &mut Box<dyn System<W> + Send>) -> SystemAccess {
+        match system.as_parallel() {
+            Some(system) => system.access(),
+            None => {
+                let mut access = SystemAccess::new();
+                access.set_exclusive();
+                access
+            }
+        }
+    }
+
+    /// Split the systems into contiguous batches not conflicting within.
+    fn batch<W: WorldMethods>(systems: &mut [Box<dyn System<W> + Send>]) -> Vec<Range<usize>> {
+        let accesses = systems.iter_mut().map(Self::access).collect::<Vec<_>>();
+        let mut batches = Vec::new();
+        let mut start = 0;
+        for (index, access) in accesses.iter().enumerate() {
+            if accesses[start..index]
+                .iter()
+                .any(|other| other.conflicts_with(access))
+            {
+                batches.push(start..index);
+                start = index;
+            }
+        }
+        if start < accesses.len() {
+            batches.push(start..accesses.len());
+        }
+
+        batches
+    }
+}
+
+impl<W: WorldMethods + 'static> Executor<W> for ParallelExecutor
+where
+    for<'w> SystemWorld<'w, W>: Send,
+{
+    type System = dyn System<W> + Send;
+
+    fn run_stage(&mut self, systems: &mut [Box<dyn System<W> + Send>], world: &mut W) {
+        let mut commands = systems.iter().map(|_| Commands::new()).collect::<Vec<_>>();
+        for batch in Self::batch(systems) {
+            let systems = &mut systems[batch.clone()];
+            let commands = &mut commands[batch];
+            if let ([system], [commands]) = (&mut *systems, &mut *commands) {
+                system.run(world, commands);
+                continue;
+            }
+
+            let change_ticks = systems
+                .iter()
+                .map(|_| world.world_mut().ticks.increment())
+                .collect::<Vec<_>>();
+            let mut system_world = SystemWorld::new(world, None, 0);
+            let split = systems
+                .iter_mut()
+                .zip(commands)
+                .zip(change_ticks)
+                .map(|((system, commands), change_tick)| {
+                    system_world.split(&Self::access(system), commands, change_tick)
+                })
+                .collect::<Vec<_>>();
+            self.pool.scope(|scope| {
+                for (system, system_world) in systems.iter_mut().zip(split) {
+                    scope.spawn(move |_| {
+                        system
+                            .as_parallel()
+                            .expect("Systems batched together are parallel systems")
+                            .run_with(system_world);
+                    });
+                }
+            });
+        }
+        commands
+            .iter_mut()
+            .for_each(|commands| commands.apply(world));
+    }
+}
+
+impl<W: WorldMethods, S: System<W> + Send + 'static> IntoBoxedSystem<dyn System<W> + Send> for S {
+    fn into_boxed(self) -> Box<dyn System<W> + Send> {
+        Box::new(self)
+    }
+}
+
+impl Default for ParallelExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for ParallelExecutor {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ParallelExecutor")
+            .field("threads", &self.pool.current_num_threads())
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "slab"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn parallel_schedule_runs_non_conflicting_systems() {
+        use std::{
+            sync::{Condvar, Mutex},
+            time::Duration,
+        };
+
+        #[derive(Debug, PartialEq)]
+        struct Position(u32);
+        #[derive(Debug, PartialEq)]
+        struct Velocity(u32);
+        #[derive(Debug, PartialEq)]
+        struct Health(u32);
+        struct Log(Vec<&'static str>);
+        /// Systems meeting here, only possible when running in parallel.
+        struct Rendezvous(Mutex<u32>, Condvar);
+
+        impl Rendezvous {
+            fn meet(&self) -> bool {
+                let mut arrived = self.0.lock().unwrap();
+                *arrived += 1;
+                self.1.notify_all();
+                let (_arrived, timeout) = self
+                    .1
+                    .wait_timeout_while(arrived, Duration::from_secs(10), |arrived| *arrived < 2)
+                    .unwrap();
+                !timeout.timed_out()
+            }
+        }
+
+        fn movement(
+            query: Query<usize, (&mut Position, &Velocity)>,
+            rendezvous: Res<Rendezvous>,
+            commands: &mut Commands<SyncWorld>,
+        ) {
+            assert!(rendezvous.meet(), "`movement` did not run in parallel");
+            for (_, (position, velocity)) in query {
+                position.0 += velocity.0;
+            }
+            commands.add(|world: &mut SyncWorld| {
+                world.resource_mut::<Log>().unwrap().0.push("movement")
+            });
+        }
+
+        fn regen(
+            query: Query<usize, &mut Health>,
+            rendezvous: Res<Rendezvous>,
+            commands: &mut Commands<SyncWorld>,
+        ) {
+            assert!(rendezvous.meet(), "`regen` did not run in parallel");
+            for (_, health) in query {
+                health.0 += 1;
+            }
+            commands
+                .add(|world: &mut SyncWorld| world.resource_mut::<Log>().unwrap().0.push("regen"));
+        }
+
+        fn fall(query: Query<usize, &mut Velocity>, mut log: ResMut<Log>) {
+            for (_, velocity) in query {
+                velocity.0 -= 1;
+            }
+            log.0.push("fall");
+        }
+
+        fn heal(world: &mut SyncWorld) {
+            world.resource_mut::<Log>().unwrap().0.push("heal");
+        }
+
+        let mut world = SyncWorld::new();
+        let a = world
+            .spawn()
+            .insert(Position(0))
+            .insert(Velocity(1))
+            .insert(Health(0))
+            .id;
+        world.insert_resource(Log(Vec::new()));
+        world.insert_resource(Rendezvous(Mutex::new(0), Condvar::new()));
+
+        let mut schedule = ParallelSchedule::with_threads(2);
+        schedule
+            .add_system(Stage::Update, movement)
+            .add_system(Stage::Update, regen)
+            .add_system(Stage::Update, fall)
+            .add_system(Stage::Update, |_: Query<usize, &Health>| {})
+            .add_system(Stage::Update, heal)
+            .add_system(Stage::Update, || {})
+            .add_system(Stage::PostUpdate, |_: Res<Log>| {})
+            .add_system(Stage::PostUpdate, |_: Res<Log>| {});
+        // `fall` conflicts with `movement` on `Velocity`, the `Health` reader with `regen`, and
+        // `heal` has exclusive access.
+        assert_eq!(
+            schedule.batches(Stage::Update),
+            vec![0..2, 2..4, 4..5, 5..6]
+        );
+        assert_eq!(schedule.batches(Stage::PostUpdate), vec![0..2]);
+        assert_eq!(schedule.batches(Stage::Startup), vec![]);
+
+        schedule.run(&mut world);
+        assert_eq!(world.component::<Position>(a), Some(&Position(1)));
+        assert_eq!(world.component::<Velocity>(a), Some(&Velocity(0)));
+        assert_eq!(world.component::<Health>(a), Some(&Health(1)));
+        // Commands are applied at the end of the stage, in the order systems were added.
+        assert_eq!(
+            world.resource::<Log>().unwrap().0,
+            vec!["fall", "heal", "movement", "regen"]
+        );
+    }
+}
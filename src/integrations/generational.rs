@@ -0,0 +1,368 @@
+use core::{
+    any::Any,
+    fmt::{Display, Formatter},
+    mem::size_of,
+};
+
+use crate::{
+    entity_builder::{EntityBuilder, EntityBuilderMethods},
+    query_builder::{EntityFrequency, QueryBuilder, QueryBuilderMethods},
+    query_mut_builder::{QueryMutBuilder, QueryMutBuilderMethods},
+    storage::Storage,
+    type_map::TypeMap,
+    world::{World, WorldMethods},
+};
+
+/// [`World`] using [`GenArena`] as entity and component storage.
+pub type GenArenaWorld = World<Entity, GenArena<TypeMap>>;
+
+/// Generational identifier, used as [`GenArena`] key.
+///
+/// The `generation` is bumped each time the `index` slot is freed, so stale
+/// ids of removed values never point to values inserted later in the same slot.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entity {
+    pub index: u32,
+    pub generation: u32,
+}
+
+impl Display for Entity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+/// Generational arena storage, keyed by [`Entity`].
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// let mut arena = GenArena::new();
+/// let a = arena.insert("a");
+/// assert_eq!(arena.remove(a), Some("a"));
+/// // Slot is re-used with a new generation...
+/// let b = arena.insert("b");
+/// assert_eq!(a.index, b.index);
+/// assert_eq!(arena.get(a), None);
+/// assert_eq!(arena.get(b), Some(&"b"));
+/// ```
+#[derive(Debug)]
+pub struct GenArena<T> {
+    slots: Vec<Slot<T>>,
+    /// Indices of vacant slots available for re-use.
+    free_indices: Vec<u32>,
+    len: usize,
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+impl<T> Default for GenArena<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free_indices: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> GenArena<T> {
+    fn slot(&self, key: Entity) -> Option<&Slot<T>> {
+        self.slots
+            .get(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)
+    }
+
+    fn slot_mut(&mut self, key: Entity) -> Option<&mut Slot<T>> {
+        self.slots
+            .get_mut(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)
+    }
+}
+
+impl<T> Storage for GenArena<T> {
+    type Key = Entity;
+
+    type Value = T;
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            ..Self::default()
+        }
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.slots
+            .reserve(additional.saturating_sub(self.free_indices.len()));
+    }
+
+    fn insert(&mut self, val: Self::Value) -> Self::Key {
+        self.len += 1;
+        if let Some(index) = self.free_indices.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.value = Some(val);
+            return Entity {
+                index,
+                generation: slot.generation,
+            };
+        }
+
+        let index = u32::try_from(self.slots.len()).expect("GenArena index overflow");
+        self.slots.push(Slot {
+            generation: 0,
+            value: Some(val),
+        });
+        Entity {
+            index,
+            generation: 0,
+        }
+    }
+
+    fn get(&self, key: Self::Key) -> Option<&Self::Value> {
+        self.slot(key)?.value.as_ref()
+    }
+
+    fn get_mut(&mut self, key: Self::Key) -> Option<&mut Self::Value> {
+        self.slot_mut(key)?.value.as_mut()
+    }
+
+    fn remove(&mut self, key: Self::Key) -> Option<Self::Value> {
+        let slot = self.slot_mut(key)?;
+        let val = slot.value.take()?;
+        // Invalidate existing keys to this slot...
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_indices.push(key.index);
+        self.len -= 1;
+
+        Some(val)
+    }
+
+    fn key_of(&self, val: &Self::Value) -> Self::Key {
+        let offset = (val as *const T as usize)
+            .checked_sub(self.slots.as_ptr() as usize)
+            .expect("Value not found in storage");
+        let index = offset / size_of::<Slot<T>>();
+        let slot = self.slots.get(index).expect("Value not found in storage");
+
+        Entity {
+            index: index as u32,
+            generation: slot.generation,
+        }
+    }
+
+    fn has(&self, key: Self::Key) -> bool {
+        self.slot(key).is_some_and(|slot| slot.value.is_some())
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (Self::Key, &Self::Value)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let key = Entity {
+                index: index as u32,
+                generation: slot.generation,
+            };
+            Some((key, slot.value.as_ref()?))
+        })
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = (Self::Key, &mut Self::Value)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let key = Entity {
+                    index: index as u32,
+                    generation: slot.generation,
+                };
+                Some((key, slot.value.as_mut()?))
+            })
+    }
+}
+
+impl WorldMethods for World<Entity, GenArena<TypeMap>> {
+    type Key = Entity;
+
+    type Erased = dyn Any;
+
+    type EntityStorage = GenArena<TypeMap>;
+
+    type ComponentStorage<T: 'static> = GenArena<T>;
+
+    type AssocEntityBuilder<'a> = EntityBuilder<'a, Self::Key, Self::EntityStorage>;
+
+    type AssocQueryBuilder<'a> = QueryBuilder<'a, Self::Key, Self::EntityStorage>;
+
+    type AssocQueryMutBuilder<'a> = QueryMutBuilder<'a, Self::Key, Self::EntityStorage>;
+
+    fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
+        self
+    }
+
+    fn world_mut(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
+        self
+    }
+}
+
+impl<'a> EntityBuilderMethods<'a> for EntityBuilder<'a, Entity, GenArena<TypeMap>> {
+    type Key = Entity;
+
+    type Erased = dyn Any;
+
+    type EntityStorage = GenArena<TypeMap>;
+
+    type ComponentStorage<T: 'static> = GenArena<T>;
+
+    fn create(id: Self::Key, world: &'a mut World<Self::Key, Self::EntityStorage>) -> Self {
+        Self::new(id, world)
+    }
+
+    fn id(&self) -> Self::Key {
+        self.id
+    }
+
+    fn world(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
+        self.world
+    }
+}
+
+impl<'a> QueryBuilderMethods<'a> for QueryBuilder<'a, Entity, GenArena<TypeMap>> {
+    type Key = Entity;
+
+    type Erased = dyn Any;
+
+    type EntityStorage = GenArena<TypeMap>;
+
+    type ComponentStorage<T: 'static> = GenArena<T>;
+
+    fn create(world: &'a World<Self::Key, Self::EntityStorage>) -> Self {
+        Self::new(world)
+    }
+
+    fn with_call_count(&mut self) -> &mut u32 {
+        &mut self.with_call_count
+    }
+
+    fn entity_freq(&self) -> &EntityFrequency<Self::Key> {
+        &self.entity_freq
+    }
+
+    fn entity_freq_mut(&mut self) -> &mut EntityFrequency<Self::Key> {
+        &mut self.entity_freq
+    }
+
+    fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
+        self.world
+    }
+}
+
+impl<'a> QueryMutBuilderMethods<'a> for QueryMutBuilder<'a, Entity, GenArena<TypeMap>> {
+    type Key = Entity;
+
+    type Erased = dyn Any;
+
+    type EntityStorage = GenArena<TypeMap>;
+
+    type ComponentStorage<T: 'static> = GenArena<T>;
+
+    fn create(world: &'a mut World<Self::Key, Self::EntityStorage>) -> Self {
+        Self::new(world)
+    }
+
+    fn with_call_count(&mut self) -> &mut u32 {
+        &mut self.with_call_count
+    }
+
+    fn entity_freq(&self) -> &EntityFrequency<Self::Key> {
+        &self.entity_freq
+    }
+
+    fn entity_freq_mut(&mut self) -> &mut EntityFrequency<Self::Key> {
+        &mut self.entity_freq
+    }
+
+    fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
+        self.world
+    }
+
+    fn world_mut(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
+        self.world
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gen_arena_storage() {
+        let mut arena = GenArena::with_capacity(2);
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+        assert_eq!(arena.len(), 2);
+        assert_eq!(arena.key_of(arena.get(b).unwrap()), b);
+
+        assert_eq!(arena.remove(a), Some(1));
+        assert_eq!(arena.remove(a), None);
+        assert!(!arena.has(a));
+
+        // Stale keys don't point to re-used slots...
+        let c = arena.insert(3);
+        assert_eq!(
+            c,
+            Entity {
+                index: 0,
+                generation: 1
+            }
+        );
+        assert_eq!(arena.get(a), None);
+        assert_eq!(arena.get_mut(a), None);
+        assert_eq!(arena.key_of(arena.get(c).unwrap()), c);
+        assert_eq!(arena.iter().collect::<Vec<_>>(), vec![(c, &3), (b, &2)]);
+        assert_eq!(c.to_string(), "0v1");
+    }
+
+    #[test]
+    fn stale_entities_are_dead() {
+        #[derive(Debug, PartialEq)]
+        struct Player(&'static str);
+
+        let mut world = GenArenaWorld::new();
+        let mike = world.spawn().insert(Player("Mike")).id;
+        assert!(world.despawn(mike));
+
+        // New entity re-uses the slot...
+        let hannah = world.spawn().insert(Player("Hannah")).id;
+        assert_eq!(mike.index, hannah.index);
+        assert_ne!(mike, hannah);
+
+        // Stale id does not point to the new entity...
+        assert_eq!(world.component_mut::<Player>(mike), None);
+        assert_eq!(world.remove_component::<Player>(mike), None);
+        assert!(!world.despawn(mike));
+        assert_eq!(
+            world.component_mut::<Player>(hannah),
+            Some(&mut Player("Hannah"))
+        );
+        let mut query = world.query();
+        let results = query.get::<&Player>().unwrap().collect::<Vec<_>>();
+        assert_eq!(results, vec![(hannah, &Player("Hannah"))]);
+    }
+}
@@ -1,2 +1,6 @@
 #[cfg(feature = "slab")]
-mod slab;
+pub(crate) mod slab;
+#[cfg(feature = "sparse_set")]
+pub(crate) mod sparse_set;
+#[cfg(feature = "generational")]
+pub(crate) mod generational;
@@ -1,10 +1,13 @@
+use core::any::Any;
+
 use slab::Slab;
 
 use crate::{
     entity_builder::{EntityBuilder, EntityBuilderMethods},
     query_builder::{EntityFrequency, QueryBuilder, QueryBuilderMethods},
+    query_mut_builder::{QueryMutBuilder, QueryMutBuilderMethods},
     storage::Storage,
-    type_map::TypeMap,
+    type_map::{SyncTypeMap, ThreadSafe, TypeMap},
     world::{World, WorldMethods},
 };
 
@@ -21,6 +24,10 @@ impl<T> Storage for Slab<T> {
         Self::with_capacity(capacity)
     }
 
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
+
     fn insert(&mut self, val: Self::Value) -> Self::Key {
         self.insert(val)
     }
@@ -33,6 +40,10 @@ impl<T> Storage for Slab<T> {
         self.get_mut(key)
     }
 
+    fn remove(&mut self, key: Self::Key) -> Option<Self::Value> {
+        self.try_remove(key)
+    }
+
     fn key_of(&self, val: &Self::Value) -> Self::Key {
         self.key_of(val)
     }
@@ -52,79 +63,173 @@ impl<T> Storage for Slab<T> {
     fn iter(&self) -> impl Iterator<Item = (Self::Key, &Self::Value)> {
         self.iter()
     }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = (Self::Key, &mut Self::Value)> {
+        self.iter_mut()
+    }
 }
 
-impl WorldMethods for World<usize, Slab<TypeMap>> {
-    type Key = usize;
+impl World<usize, Slab<TypeMap>> {
+    /// Create a new [`World`] using [`Slab`] storage.
+    ///
+    /// Makes `World::new()` default to Slab storage when other storage integrations are enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
 
-    type EntityStorage = Slab<TypeMap>;
+/// Slab [`World`] of the thread-safe flavour, only storing `Send + Sync` components, resources
+/// and events, making the world itself `Send + Sync`.
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// struct Position(f32);
+///
+/// let mut world = SyncWorld::new();
+/// let entity = world.spawn().insert(Position(1.0)).id;
+/// let world = std::thread::spawn(move || world).join().unwrap();
+/// assert_eq!(world.component::<Position>(entity).unwrap().0, 1.0);
+/// ```
+///
+/// Components that are not `Send + Sync` can not be inserted:
+///
+/// ```compile_fail
+/// # use anvaya::prelude::*;
+/// let mut world = SyncWorld::new();
+/// world.spawn().insert(std::rc::Rc::new(1));
+/// ```
+pub type SyncWorld = World<usize, Slab<SyncTypeMap>>;
 
-    type ComponentStorage<T: 'static> = Slab<T>;
+/// Impl the world and builder traits for Slab worlds storing `$erased` values.
+macro_rules! impl_slab_world {
+    ($type_map:ty, $erased:ty) => {
+        impl WorldMethods for World<usize, Slab<$type_map>> {
+            type Key = usize;
 
-    type AssocEntityBuilder<'a> = EntityBuilder<'a, Self::Key, Self::EntityStorage>;
+            type Erased = $erased;
 
-    type AssocQueryBuilder<'a> = QueryBuilder<'a, Self::Key, Self::EntityStorage>;
+            type EntityStorage = Slab<$type_map>;
 
-    fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
-        self
-    }
+            type ComponentStorage<T: 'static>
+                = Slab<T>
+            where
+                ThreadSafe<Self::Erased, T>: Send + Sync;
 
-    fn world_mut(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
-        self
-    }
-}
+            type AssocEntityBuilder<'a> = EntityBuilder<'a, Self::Key, Self::EntityStorage>;
 
-impl<'a> EntityBuilderMethods<'a> for EntityBuilder<'a, usize, Slab<TypeMap>> {
-    type Key = usize;
+            type AssocQueryBuilder<'a> = QueryBuilder<'a, Self::Key, Self::EntityStorage>;
 
-    type EntityStorage = Slab<TypeMap>;
+            type AssocQueryMutBuilder<'a> = QueryMutBuilder<'a, Self::Key, Self::EntityStorage>;
 
-    type ComponentStorage<T: 'static> = Slab<T>;
+            fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
+                self
+            }
 
-    fn create(id: Self::Key, world: &'a mut World<Self::Key, Self::EntityStorage>) -> Self {
-        Self::new(id, world)
-    }
+            fn world_mut(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
+                self
+            }
+        }
 
-    fn id(&self) -> Self::Key {
-        self.id
-    }
+        impl<'a> EntityBuilderMethods<'a> for EntityBuilder<'a, usize, Slab<$type_map>> {
+            type Key = usize;
 
-    fn world(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
-        self.world
-    }
-}
+            type Erased = $erased;
 
-impl<'a> QueryBuilderMethods<'a> for QueryBuilder<'a, usize, Slab<TypeMap>> {
-    type Key = usize;
+            type EntityStorage = Slab<$type_map>;
 
-    type EntityStorage = Slab<TypeMap>;
+            type ComponentStorage<T: 'static>
+                = Slab<T>
+            where
+                ThreadSafe<Self::Erased, T>: Send + Sync;
 
-    type ComponentStorage<T: 'static> = Slab<T>;
+            fn create(id: Self::Key, world: &'a mut World<Self::Key, Self::EntityStorage>) -> Self {
+                Self::new(id, world)
+            }
 
-    fn create(world: &'a World<Self::Key, Self::EntityStorage>) -> Self {
-        Self::new(world)
-    }
+            fn id(&self) -> Self::Key {
+                self.id
+            }
 
-    fn with_call_count(&mut self) -> &mut u32 {
-        &mut self.with_call_count
-    }
+            fn world(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
+                self.world
+            }
+        }
 
-    fn entity_freq(&self) -> &EntityFrequency<Self::Key> {
-        &self.entity_freq
-    }
+        impl<'a> QueryBuilderMethods<'a> for QueryBuilder<'a, usize, Slab<$type_map>> {
+            type Key = usize;
 
-    fn entity_freq_mut(&mut self) -> &mut EntityFrequency<Self::Key> {
-        &mut self.entity_freq
-    }
+            type Erased = $erased;
 
-    fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
-        self.world
-    }
+            type EntityStorage = Slab<$type_map>;
+
+            type ComponentStorage<T: 'static> = Slab<T>;
+
+            fn create(world: &'a World<Self::Key, Self::EntityStorage>) -> Self {
+                Self::new(world)
+            }
+
+            fn with_call_count(&mut self) -> &mut u32 {
+                &mut self.with_call_count
+            }
+
+            fn entity_freq(&self) -> &EntityFrequency<Self::Key> {
+                &self.entity_freq
+            }
+
+            fn entity_freq_mut(&mut self) -> &mut EntityFrequency<Self::Key> {
+                &mut self.entity_freq
+            }
+
+            fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
+                self.world
+            }
+        }
+
+        impl<'a> QueryMutBuilderMethods<'a> for QueryMutBuilder<'a, usize, Slab<$type_map>> {
+            type Key = usize;
+
+            type Erased = $erased;
+
+            type EntityStorage = Slab<$type_map>;
+
+            type ComponentStorage<T: 'static> = Slab<T>;
+
+            fn create(world: &'a mut World<Self::Key, Self::EntityStorage>) -> Self {
+                Self::new(world)
+            }
+
+            fn with_call_count(&mut self) -> &mut u32 {
+                &mut self.with_call_count
+            }
+
+            fn entity_freq(&self) -> &EntityFrequency<Self::Key> {
+                &self.entity_freq
+            }
+
+            fn entity_freq_mut(&mut self) -> &mut EntityFrequency<Self::Key> {
+                &mut self.entity_freq
+            }
+
+            fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
+                self.world
+            }
+
+            fn world_mut(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
+                self.world
+            }
+        }
+    };
 }
 
+impl_slab_world!(TypeMap, dyn Any);
+impl_slab_world!(SyncTypeMap, dyn Any + Send + Sync);
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::world::tests::storage;
 
     #[test]
     fn readme_example() {
@@ -142,7 +247,7 @@ mod test {
         let mut query = world.query();
         let mut results = query
             .with::<Age>()
-            .get::<Player>()
+            .get::<&Player>()
             .unwrap()
             .map(|(_, player)| player.0);
 
@@ -150,4 +255,516 @@ mod test {
         assert_eq!(results.next().unwrap(), "Mike");
         assert_eq!(results.next().unwrap(), "Hannah");
     }
+
+    #[test]
+    fn despawn_frees_components() {
+        struct Player(&'static str);
+        #[allow(dead_code)]
+        struct Age(u8);
+
+        let mut world = World::new();
+        let mike = world.spawn().insert(Player("Mike")).insert(Age(30)).id;
+        let hannah = world.spawn().insert(Player("Hannah")).id;
+
+        assert!(world.despawn(mike));
+        assert!(!world.despawn(mike));
+        assert!(world.component_mut::<Player>(mike).is_none());
+
+        // Component slots are freed from their tables...
+        assert_eq!(storage::<Player>(&world).unwrap().len(), 1);
+        assert!(storage::<Age>(&world).unwrap().is_empty());
+
+        // Remaining entities are untouched...
+        let mut query = world.query();
+        let results = query
+            .with::<Player>()
+            .get::<&Player>()
+            .unwrap()
+            .map(|(entity, player)| (entity, player.0))
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![(hannah, "Hannah")]);
+    }
+
+    #[test]
+    fn remove_component_returns_value() {
+        #[derive(Debug, PartialEq)]
+        struct Player(&'static str);
+        #[derive(Debug, PartialEq)]
+        struct Stunned(u8);
+
+        let mut world = World::new();
+        let mike = world.spawn().insert(Player("Mike")).insert(Stunned(3)).id;
+
+        assert_eq!(world.remove_component::<Stunned>(mike), Some(Stunned(3)));
+        assert_eq!(world.remove_component::<Stunned>(mike), None);
+        assert!(world.component_mut::<Stunned>(mike).is_none());
+        assert_eq!(
+            world.component_mut::<Player>(mike),
+            Some(&mut Player("Mike"))
+        );
+
+        // Entity no longer matches queries on the removed component...
+        let mut query = world.query();
+        assert_eq!(query.with::<Stunned>().get::<&Player>().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn insert_existing_replaces_in_place() {
+        #[derive(Debug, PartialEq)]
+        struct Hp(u8);
+
+        let mut world = World::new();
+        let mut builder = world.spawn();
+        builder.insert(Hp(100)).insert(Hp(90));
+        assert_eq!(builder.replace(Hp(80)), Some(Hp(90)));
+        assert_eq!(builder.try_insert(Hp(70)).err(), Some(Hp(70)));
+        let entity = builder.id;
+
+        // Only a single value is stored in the table...
+        assert_eq!(storage::<Hp>(&world).unwrap().len(), 1);
+        assert_eq!(world.component_mut::<Hp>(entity), Some(&mut Hp(80)));
+
+        // Try insert works for new components...
+        world.remove_component::<Hp>(entity);
+        let mut builder = EntityBuilder::new(entity, &mut world);
+        assert!(builder.try_insert(Hp(60)).is_ok());
+        assert_eq!(world.component_mut::<Hp>(entity), Some(&mut Hp(60)));
+    }
+
+    #[test]
+    fn get_multiple_components() {
+        struct Player(&'static str);
+        struct Age(u8);
+        struct Team(&'static str);
+
+        let mut world = World::new();
+        world
+            .spawn()
+            .insert(Player("Mike"))
+            .insert(Age(30))
+            .insert(Team("Red"));
+        world.spawn().insert(Player("Hannah")).insert(Age(25));
+        world
+            .spawn()
+            .insert(Player("Sam"))
+            .insert(Team("Blue"))
+            .insert(Age(22));
+
+        let mut query = world.query();
+        let results = query
+            .with::<Player>()
+            .get::<(&Player, &Age, &Team)>()
+            .unwrap()
+            .map(|(_, (player, age, team))| (player.0, age.0, team.0))
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![("Mike", 30, "Red"), ("Sam", 22, "Blue")]);
+    }
+
+    #[test]
+    fn get_mut_updates_components() {
+        #[derive(Debug, PartialEq)]
+        struct Position(i32);
+        struct Velocity(i32);
+        struct Frozen;
+
+        let mut world = World::new();
+        let a = world.spawn().insert(Position(0)).insert(Velocity(2)).id;
+        let b = world.spawn().insert(Position(10)).insert(Velocity(-1)).id;
+        let c = world.spawn().insert(Position(5)).insert(Frozen).id;
+
+        let mut query = world.query_mut();
+        for (_, (position, velocity)) in query
+            .with::<Position>()
+            .get_mut::<(&mut Position, &Velocity)>()
+            .unwrap()
+        {
+            position.0 += velocity.0;
+        }
+        assert_eq!(world.component_mut::<Position>(a), Some(&mut Position(2)));
+        assert_eq!(world.component_mut::<Position>(b), Some(&mut Position(9)));
+        assert_eq!(world.component_mut::<Position>(c), Some(&mut Position(5)));
+
+        let mut query = world.query_mut();
+        query
+            .with::<Frozen>()
+            .get_mut::<&mut Position>()
+            .unwrap()
+            .for_each(|(_, position)| position.0 = 0);
+        assert_eq!(world.component_mut::<Position>(c), Some(&mut Position(0)));
+        assert_eq!(world.component_mut::<Position>(b), Some(&mut Position(9)));
+    }
+
+    #[test]
+    fn get_mut_out_of_table_order() {
+        #[derive(Debug, PartialEq)]
+        struct Score(u32);
+
+        let mut world = World::new();
+        let a = world.spawn().insert(Score(0)).id;
+        let b = world.spawn().insert(Score(1)).id;
+        let c = world.spawn().insert(Score(2)).id;
+        world.remove_component::<Score>(a);
+        world.remove_component::<Score>(c);
+        // Reuses the freed table slots, in reverse of the entity order...
+        let d = world.spawn().insert(Score(3)).id;
+        let e = world.spawn().insert(Score(4)).id;
+
+        let mut query = world.query_mut();
+        let scored = query
+            .with::<Score>()
+            .get_mut::<&mut Score>()
+            .unwrap()
+            .map(|(entity, score)| {
+                score.0 += 10;
+                entity
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(scored.len(), 3);
+        assert_eq!(world.component::<Score>(b), Some(&Score(11)));
+        assert_eq!(world.component::<Score>(d), Some(&Score(13)));
+        assert_eq!(world.component::<Score>(e), Some(&Score(14)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Component accessed more than once")]
+    fn get_mut_conflicting_access_panics() {
+        struct Position(#[allow(dead_code)] i32);
+
+        let mut world = World::new();
+        world.spawn().insert(Position(0));
+
+        let mut query = world.query_mut();
+        let _ = query
+            .with::<Position>()
+            .get_mut::<(&mut Position, &Position)>();
+    }
+
+    #[test]
+    fn without_excludes_entities() {
+        struct Player(&'static str);
+        struct Age(u8);
+        struct Dead;
+
+        let mut world = World::new();
+        world
+            .spawn()
+            .insert(Player("Mike"))
+            .insert(Age(30))
+            .insert(Dead);
+        world.spawn().insert(Player("Hannah")).insert(Age(25));
+        world.spawn().insert(Player("Sam")).insert(Dead);
+        world.spawn().insert(Player("Rahul")).insert(Age(18));
+
+        let mut query = world.query();
+        let alive = query
+            .with::<Player>()
+            .without::<Dead>()
+            .with::<Age>()
+            .get::<&Player>()
+            .unwrap()
+            .map(|(_, player)| player.0)
+            .collect::<Vec<_>>();
+        assert_eq!(alive, vec!["Hannah", "Rahul"]);
+
+        let mut query = world.query_mut();
+        query
+            .with::<Age>()
+            .without::<Dead>()
+            .get_mut::<&mut Age>()
+            .unwrap()
+            .for_each(|(_, age)| age.0 += 1);
+        let mut query = world.query();
+        let ages = query
+            .with::<Age>()
+            .get::<&Age>()
+            .unwrap()
+            .map(|(_, age)| age.0)
+            .collect::<Vec<_>>();
+        assert_eq!(ages, vec![30, 26, 19]);
+    }
+
+    #[test]
+    fn query_semantics_empty_world() {
+        struct Player;
+
+        let world = World::new();
+        // No table for the fetched component...
+        assert!(world.query().get::<&Player>().is_none());
+        assert!(world.query().with::<Player>().get::<&Player>().is_none());
+    }
+
+    #[test]
+    fn query_semantics_get_without_filters() {
+        struct Player(&'static str);
+        struct Age;
+
+        let mut world = World::new();
+        world.spawn().insert(Player("Mike"));
+        world.spawn().insert(Age);
+        world.spawn().insert(Player("Hannah")).insert(Age);
+
+        // All entities having the fetched components match...
+        let mut query = world.query();
+        let players = query
+            .get::<&Player>()
+            .unwrap()
+            .map(|(_, player)| player.0)
+            .collect::<Vec<_>>();
+        assert_eq!(players, vec!["Mike", "Hannah"]);
+        let mut query = world.query();
+        assert_eq!(query.get::<(&Player, &Age)>().unwrap().count(), 1);
+    }
+
+    #[test]
+    fn query_semantics_with_unknown_component() {
+        struct Player;
+        struct NeverInserted;
+
+        let mut world = World::new();
+        world.spawn().insert(Player);
+        world.spawn().insert(Player);
+
+        // Filter on a never inserted type matches nothing...
+        let mut query = world.query();
+        assert_eq!(
+            query
+                .with::<NeverInserted>()
+                .get::<&Player>()
+                .unwrap()
+                .count(),
+            0
+        );
+        let mut query = world.query();
+        let count = query
+            .with::<Player>()
+            .with::<NeverInserted>()
+            .get::<&Player>()
+            .unwrap()
+            .count();
+        assert_eq!(count, 0);
+        let mut query = world.query_mut();
+        let count = query
+            .with::<NeverInserted>()
+            .get_mut::<&mut Player>()
+            .unwrap()
+            .count();
+        assert_eq!(count, 0);
+
+        // Excluding a never inserted type excludes nothing...
+        let mut query = world.query();
+        assert_eq!(
+            query
+                .without::<NeverInserted>()
+                .get::<&Player>()
+                .unwrap()
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn query_semantics_with_removed_components() {
+        struct Player;
+
+        let mut world = World::new();
+        let mike = world.spawn().insert(Player).id;
+        world.despawn(mike);
+
+        // Table exists but is empty...
+        let mut query = world.query();
+        assert_eq!(query.with::<Player>().get::<&Player>().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn query_semantics_repeated_with() {
+        struct Player;
+        struct Age;
+
+        let mut world = World::new();
+        world.spawn().insert(Player).insert(Age);
+        world.spawn().insert(Player);
+
+        // Repeating a filter does not change results...
+        let mut query = world.query();
+        let count = query
+            .with::<Age>()
+            .with::<Age>()
+            .get::<&Player>()
+            .unwrap()
+            .count();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn get_optional_components() {
+        struct Player(&'static str);
+        struct Age(u8);
+        struct NeverInserted;
+
+        let mut world = World::new();
+        world.spawn().insert(Player("Mike")).insert(Age(30));
+        world.spawn().insert(Player("Hannah"));
+
+        let mut query = world.query();
+        let results = query
+            .with::<Player>()
+            .get::<(&Player, Option<&Age>, Option<&NeverInserted>)>()
+            .unwrap()
+            .map(|(_, (player, age, never))| (player.0, age.map(|age| age.0), never.is_some()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![("Mike", Some(30), false), ("Hannah", None, false)]
+        );
+
+        let mut query = world.query_mut();
+        query
+            .get_mut::<(&Player, Option<&mut Age>)>()
+            .unwrap()
+            .for_each(|(_, (_, age))| {
+                if let Some(age) = age {
+                    age.0 += 1;
+                }
+            });
+        let mut query = world.query();
+        let ages = query
+            .get::<&Age>()
+            .unwrap()
+            .map(|(_, age)| age.0)
+            .collect::<Vec<_>>();
+        assert_eq!(ages, vec![31]);
+    }
+
+    #[test]
+    fn any_of_matches_either_component() {
+        struct Player(&'static str);
+        struct Footballer;
+        struct Cricketer;
+        struct Injured;
+        struct NeverInserted;
+
+        let mut world = World::new();
+        world.spawn().insert(Player("Mike")).insert(Footballer);
+        world
+            .spawn()
+            .insert(Player("Rahul"))
+            .insert(Footballer)
+            .insert(Cricketer);
+        world
+            .spawn()
+            .insert(Player("Sam"))
+            .insert(Cricketer)
+            .insert(Injured);
+        world.spawn().insert(Player("Hannah"));
+
+        // Entities having both are matched once...
+        let mut query = world.query();
+        let players = query
+            .any_of::<(Footballer, Cricketer)>()
+            .get::<&Player>()
+            .unwrap()
+            .map(|(_, player)| player.0)
+            .collect::<Vec<_>>();
+        assert_eq!(players, vec!["Mike", "Rahul", "Sam"]);
+
+        // Combines with other filters...
+        let mut query = world.query();
+        let players = query
+            .with::<Player>()
+            .any_of::<(Cricketer, NeverInserted)>()
+            .without::<Injured>()
+            .get::<&Player>()
+            .unwrap()
+            .map(|(_, player)| player.0)
+            .collect::<Vec<_>>();
+        assert_eq!(players, vec!["Rahul"]);
+
+        // Never inserted components match nothing...
+        let mut query = world.query_mut();
+        let count = query
+            .any_of::<(NeverInserted,)>()
+            .get_mut::<&mut Player>()
+            .unwrap()
+            .count();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn added_and_changed_filters() {
+        #[derive(Debug, PartialEq)]
+        struct Position(i32);
+        struct Velocity(i32);
+
+        let mut world = World::new();
+        let a = world.spawn().insert(Position(0)).insert(Velocity(1)).id;
+        let b = world.spawn().insert(Position(0)).id;
+
+        // Everything is added (and changed) initially...
+        let mut query = world.query();
+        assert_eq!(
+            query
+                .added::<Position>()
+                .get::<&Position>()
+                .unwrap()
+                .count(),
+            2
+        );
+
+        world.clear_trackers();
+        let mut query = world.query();
+        assert_eq!(
+            query
+                .added::<Position>()
+                .get::<&Position>()
+                .unwrap()
+                .count(),
+            0
+        );
+        let mut query = world.query();
+        assert_eq!(
+            query
+                .changed::<Position>()
+                .get::<&Position>()
+                .unwrap()
+                .count(),
+            0
+        );
+
+        // Mutable iteration and access mark components as changed...
+        let mut query = world.query_mut();
+        query
+            .with::<Velocity>()
+            .get_mut::<(&mut Position, &Velocity)>()
+            .unwrap()
+            .for_each(|(_, (position, velocity))| position.0 += velocity.0);
+        let c = world.spawn().insert(Position(5)).id;
+        let mut query = world.query();
+        let changed = query
+            .changed::<Position>()
+            .get::<&Position>()
+            .unwrap()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        assert_eq!(changed, vec![a, c]);
+        let mut query = world.query();
+        let added = query
+            .added::<Position>()
+            .get::<&Position>()
+            .unwrap()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        assert_eq!(added, vec![c]);
+
+        world.clear_trackers();
+        world.component_mut::<Position>(b).unwrap().0 = 1;
+        world.spawn().insert(Velocity(0));
+        let mut query = world.query_mut();
+        let changed = query
+            .changed::<Position>()
+            .get_mut::<&Position>()
+            .unwrap()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        assert_eq!(changed, vec![b]);
+    }
 }
@@ -0,0 +1,337 @@
+use core::{any::Any, mem::size_of};
+
+use crate::{
+    entity_builder::{EntityBuilder, EntityBuilderMethods},
+    query_builder::{EntityFrequency, QueryBuilder, QueryBuilderMethods},
+    query_mut_builder::{QueryMutBuilder, QueryMutBuilderMethods},
+    storage::Storage,
+    type_map::TypeMap,
+    world::{World, WorldMethods},
+};
+
+/// [`World`] using [`SparseSet`] as entity and component storage.
+pub type SparseSetWorld = World<usize, SparseSet<TypeMap>>;
+
+/// Sparse set storage.
+///
+/// - Values are packed in a dense array, making iteration cache friendly.
+/// - Keys index into a sparse array pointing at the dense values, for O(1) lookups.
+/// - Removal swaps the last dense value into the removed slot, for O(1) removal.
+///
+/// [`Storage::key_of`] panics for zero sized values, as their refs can't be told apart.
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// let mut set = SparseSet::new();
+/// let a = set.insert("a");
+/// let b = set.insert("b");
+/// assert_eq!(set.remove(a), Some("a"));
+/// assert_eq!(set.get(b), Some(&"b"));
+/// // Keys of removed values are re-used...
+/// assert_eq!(set.insert("c"), a);
+/// ```
+#[derive(Debug)]
+pub struct SparseSet<T> {
+    /// Packed values.
+    dense: Vec<T>,
+    /// Keys of the packed values, by dense index.
+    dense_keys: Vec<usize>,
+    /// Dense index of each key (`None` if vacant).
+    sparse: Vec<Option<usize>>,
+    /// Vacant keys available for re-use.
+    free_keys: Vec<usize>,
+}
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        Self {
+            dense: Vec::new(),
+            dense_keys: Vec::new(),
+            sparse: Vec::new(),
+            free_keys: Vec::new(),
+        }
+    }
+}
+
+impl<T> Storage for SparseSet<T> {
+    type Key = usize;
+
+    type Value = T;
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            dense: Vec::with_capacity(capacity),
+            dense_keys: Vec::with_capacity(capacity),
+            sparse: Vec::with_capacity(capacity),
+            free_keys: Vec::new(),
+        }
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.dense.reserve(additional);
+        self.dense_keys.reserve(additional);
+        self.sparse
+            .reserve(additional.saturating_sub(self.free_keys.len()));
+    }
+
+    fn insert(&mut self, val: Self::Value) -> Self::Key {
+        let key = if let Some(key) = self.free_keys.pop() {
+            key
+        } else {
+            self.sparse.push(None);
+            self.sparse.len() - 1
+        };
+        self.sparse[key] = Some(self.dense.len());
+        self.dense.push(val);
+        self.dense_keys.push(key);
+
+        key
+    }
+
+    fn get(&self, key: Self::Key) -> Option<&Self::Value> {
+        let index = (*self.sparse.get(key)?)?;
+        self.dense.get(index)
+    }
+
+    fn get_mut(&mut self, key: Self::Key) -> Option<&mut Self::Value> {
+        let index = (*self.sparse.get(key)?)?;
+        self.dense.get_mut(index)
+    }
+
+    fn remove(&mut self, key: Self::Key) -> Option<Self::Value> {
+        let index = self.sparse.get_mut(key)?.take()?;
+        let val = self.dense.swap_remove(index);
+        self.dense_keys.swap_remove(index);
+        // Point the key of the value swapped into the removed slot...
+        if let Some(swapped_key) = self.dense_keys.get(index) {
+            self.sparse[*swapped_key] = Some(index);
+        }
+        self.free_keys.push(key);
+
+        Some(val)
+    }
+
+    fn key_of(&self, val: &Self::Value) -> Self::Key {
+        let size = size_of::<T>();
+        assert!(size != 0, "Keys of zero sized values can't be told apart");
+        let index = (val as *const T as usize)
+            .checked_sub(self.dense.as_ptr() as usize)
+            .expect("Value not found in storage")
+            / size;
+
+        *self
+            .dense_keys
+            .get(index)
+            .expect("Value not found in storage")
+    }
+
+    fn has(&self, key: Self::Key) -> bool {
+        self.sparse.get(key).is_some_and(|index| index.is_some())
+    }
+
+    fn len(&self) -> usize {
+        self.dense.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (Self::Key, &Self::Value)> {
+        self.dense_keys.iter().copied().zip(self.dense.iter())
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = (Self::Key, &mut Self::Value)> {
+        self.dense_keys.iter().copied().zip(self.dense.iter_mut())
+    }
+}
+
+impl WorldMethods for World<usize, SparseSet<TypeMap>> {
+    type Key = usize;
+
+    type Erased = dyn Any;
+
+    type EntityStorage = SparseSet<TypeMap>;
+
+    type ComponentStorage<T: 'static> = SparseSet<T>;
+
+    type AssocEntityBuilder<'a> = EntityBuilder<'a, Self::Key, Self::EntityStorage>;
+
+    type AssocQueryBuilder<'a> = QueryBuilder<'a, Self::Key, Self::EntityStorage>;
+
+    type AssocQueryMutBuilder<'a> = QueryMutBuilder<'a, Self::Key, Self::EntityStorage>;
+
+    fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
+        self
+    }
+
+    fn world_mut(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
+        self
+    }
+}
+
+impl<'a> EntityBuilderMethods<'a> for EntityBuilder<'a, usize, SparseSet<TypeMap>> {
+    type Key = usize;
+
+    type Erased = dyn Any;
+
+    type EntityStorage = SparseSet<TypeMap>;
+
+    type ComponentStorage<T: 'static> = SparseSet<T>;
+
+    fn create(id: Self::Key, world: &'a mut World<Self::Key, Self::EntityStorage>) -> Self {
+        Self::new(id, world)
+    }
+
+    fn id(&self) -> Self::Key {
+        self.id
+    }
+
+    fn world(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
+        self.world
+    }
+}
+
+impl<'a> QueryBuilderMethods<'a> for QueryBuilder<'a, usize, SparseSet<TypeMap>> {
+    type Key = usize;
+
+    type Erased = dyn Any;
+
+    type EntityStorage = SparseSet<TypeMap>;
+
+    type ComponentStorage<T: 'static> = SparseSet<T>;
+
+    fn create(world: &'a World<Self::Key, Self::EntityStorage>) -> Self {
+        Self::new(world)
+    }
+
+    fn with_call_count(&mut self) -> &mut u32 {
+        &mut self.with_call_count
+    }
+
+    fn entity_freq(&self) -> &EntityFrequency<Self::Key> {
+        &self.entity_freq
+    }
+
+    fn entity_freq_mut(&mut self) -> &mut EntityFrequency<Self::Key> {
+        &mut self.entity_freq
+    }
+
+    fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
+        self.world
+    }
+}
+
+impl<'a> QueryMutBuilderMethods<'a> for QueryMutBuilder<'a, usize, SparseSet<TypeMap>> {
+    type Key = usize;
+
+    type Erased = dyn Any;
+
+    type EntityStorage = SparseSet<TypeMap>;
+
+    type ComponentStorage<T: 'static> = SparseSet<T>;
+
+    fn create(world: &'a mut World<Self::Key, Self::EntityStorage>) -> Self {
+        Self::new(world)
+    }
+
+    fn with_call_count(&mut self) -> &mut u32 {
+        &mut self.with_call_count
+    }
+
+    fn entity_freq(&self) -> &EntityFrequency<Self::Key> {
+        &self.entity_freq
+    }
+
+    fn entity_freq_mut(&mut self) -> &mut EntityFrequency<Self::Key> {
+        &mut self.entity_freq
+    }
+
+    fn world(&self) -> &World<Self::Key, Self::EntityStorage> {
+        self.world
+    }
+
+    fn world_mut(&mut self) -> &mut World<Self::Key, Self::EntityStorage> {
+        self.world
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sparse_set_storage() {
+        #[derive(Debug, PartialEq)]
+        struct Hp(u8);
+
+        let mut set = SparseSet::with_capacity(4);
+        let a = set.insert(Hp(1));
+        let b = set.insert(Hp(2));
+        let c = set.insert(Hp(3));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.key_of(set.get(b).unwrap()), b);
+
+        // Removal swaps in the last value...
+        assert_eq!(set.remove(a), Some(Hp(1)));
+        assert_eq!(set.remove(a), None);
+        assert!(!set.has(a));
+        assert_eq!(set.get(c), Some(&Hp(3)));
+        assert_eq!(set.key_of(set.get(c).unwrap()), c);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![(c, &Hp(3)), (b, &Hp(2))]
+        );
+
+        // Vacant keys are re-used...
+        assert_eq!(set.insert(Hp(4)), a);
+        set.iter_mut().for_each(|(_, hp)| hp.0 *= 10);
+        assert_eq!(set.get(a), Some(&Hp(40)));
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Keys of zero sized values can't be told apart")]
+    fn sparse_set_zero_sized_key_of_panics() {
+        struct Marker;
+
+        let mut set = SparseSet::new();
+        set.insert(Marker);
+        let b = set.insert(Marker);
+        set.key_of(set.get(b).unwrap());
+    }
+
+    #[test]
+    fn sparse_set_world() {
+        #[derive(Debug, PartialEq)]
+        struct Player(&'static str);
+        struct Age(u8);
+
+        let mut world = SparseSetWorld::new();
+        let mike = world.spawn().insert(Player("Mike")).insert(Age(30)).id;
+        world.spawn().insert(Player("Hannah")).insert(Age(25));
+        world.spawn().insert(Player("Sam"));
+
+        let mut query = world.query_mut();
+        query
+            .get_mut::<&mut Age>()
+            .unwrap()
+            .for_each(|(_, age)| age.0 += 1);
+
+        assert!(world.despawn(mike));
+        let mut query = world.query();
+        let results = query
+            .with::<Age>()
+            .get::<(&Player, &Age)>()
+            .unwrap()
+            .map(|(_, (player, age))| (player.0, age.0))
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![("Hannah", 26)]);
+    }
+}
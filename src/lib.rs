@@ -1,10 +1,39 @@
 #![doc = include_str!("../README.md")]
 
+// Allow derive macros (using `::anvaya` paths) within this crate, eg. in tests.
+extern crate self as anvaya;
+
+/// Archetype based world backend.
+#[cfg(feature = "archetype")]
+mod archetype;
+/// Groups of components inserted together.
+mod bundle;
+/// Deferred structural changes.
+mod commands;
 mod entity_builder;
+/// Typed event channels.
+mod events;
+/// Parallel system executor.
+#[cfg(feature = "rayon")]
+mod executor;
 /// Various storage integrations.
 mod integrations;
+/// Parallel iteration of query results.
+#[cfg(feature = "rayon")]
+mod parallel;
 mod query_builder;
+mod query_data;
+mod query_mut_builder;
+/// Component registry with type-erased hooks.
+mod registry;
+/// Schedule running systems by stages.
+mod schedule;
+/// World (de)serialization via serde.
+#[cfg(feature = "serde")]
+mod snapshot;
 mod storage;
+/// Systems and their parameters.
+mod system;
 /// Type Map data structure.
 mod type_map;
 mod world;
@@ -13,14 +42,60 @@ mod world;
 pub mod prelude {
     use super::*;
 
+    /// Archetype world backend exports.
+    #[cfg(feature = "archetype")]
+    pub use archetype::{
+        ArchetypeEntityBuilder, ArchetypeQueryBuilder, ArchetypeQueryData, ArchetypeQueryDataMut,
+        ArchetypeQueryMutBuilder, ArchetypeWorld,
+    };
+    /// Bundle exports for inserting groups of components.
+    pub use bundle::Bundle;
+    /// Bundle derive export.
+    #[cfg(feature = "derive")]
+    pub use anvaya_derive::Bundle;
+    /// Command buffer exports for deferred structural changes.
+    pub use commands::{Commands, SpawnCommands};
     /// Entity builder exports for external impls.
     pub use entity_builder::{EntityBuilder, EntityBuilderMethods};
+    /// Event exports for reading events.
+    pub use events::EventReader;
+    /// Parallel schedule exports for running systems on a thread pool.
+    #[cfg(feature = "rayon")]
+    pub use executor::{ParallelExecutor, ParallelSchedule};
+    /// Parallel query iteration exports, incl rayon's `ParallelIterator` to work on the results.
+    #[cfg(feature = "rayon")]
+    pub use {parallel::ParallelQueryIter, rayon::iter::ParallelIterator};
     /// Query builder exports for external impls.
-    pub use query_builder::{EntityFrequency, QueryBuilder, QueryBuilderMethods};
+    pub use query_builder::{ComponentSet, EntityFrequency, QueryBuilder, QueryBuilderMethods};
+    /// Query data exports for fetching components in queries.
+    pub use query_data::{QueryData, QueryDataMut};
+    /// Mutable query builder exports for external impls.
+    pub use query_mut_builder::{QueryMutBuilder, QueryMutBuilderMethods};
+    /// Component registry exports for inspecting components.
+    pub use registry::{ComponentInfo, ComponentRegistry, RegisterComponent};
+    /// Schedule exports for running systems.
+    pub use schedule::{Executor, IntoBoxedSystem, Schedule, SequentialExecutor, Stage};
+    /// World serialization exports.
+    #[cfg(feature = "serde")]
+    pub use snapshot::{DeserializedWorld, SerializableWorld};
     /// Storage exports for external impls.
     pub use storage::{Identifier, Storage};
-    /// Export [`TypeMap`] for re-use with external storage impls.
-    pub use type_map::TypeMap;
+    /// Thread-safe Slab world export.
+    #[cfg(feature = "slab")]
+    pub use integrations::slab::SyncWorld;
+    /// Generational storage exports.
+    #[cfg(feature = "generational")]
+    pub use integrations::generational::{Entity, GenArena, GenArenaWorld};
+    /// Sparse set storage exports.
+    #[cfg(feature = "sparse_set")]
+    pub use integrations::sparse_set::{SparseSet, SparseSetWorld};
+    /// System exports for writing systems and their parameters.
+    pub use system::{
+        Added, Changed, Exclusive, FunctionSystem, IntoSystem, ParallelSystem, Query, QueryFilter,
+        Res, ResMut, System, SystemAccess, SystemParam, SystemWorld, With, Without,
+    };
+    /// Export [`TypeMap`] for re-use with external storage impls, incl its thread-safe flavour.
+    pub use type_map::{AnyValue, SyncTypeMap, ThreadSafe, TypeMap};
     /// Exports world, traits etc for external storage impls.
-    pub use world::{World, WorldMethods};
+    pub use world::{EntityTypeMap, TableStorage, World, WorldMethods, WorldValue};
 }
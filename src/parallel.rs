@@ -0,0 +1,109 @@
+use rayon::{
+    iter::{IntoParallelIterator, ParallelIterator},
+    vec::IntoIter,
+};
+
+/// Parallel iteration over query results via rayon.
+///
+/// Implemented for all iterators with `Send` items, eg. the results of
+/// [`QueryBuilderMethods::get`](crate::prelude::QueryBuilderMethods::get),
+/// [`QueryMutBuilderMethods::get_mut`](crate::prelude::QueryMutBuilderMethods::get_mut) and the
+/// [`Query`](crate::prelude::Query) system param. Components fetched by ref (`&C`) need to be
+/// `Sync` and ones fetched by mutable ref (`&mut C`) need to be `Send`.
+///
+/// __Note: Matching entities is sequential, only the work on the results runs in parallel.__
+/// The results are first collected into a `Vec` (one allocation of the result count), so
+/// parallelism only pays off when the per-entity work outweighs the collection.
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// struct Position(f32);
+/// struct Velocity(f32);
+///
+/// let mut world = World::new();
+/// for i in 0..100 {
+///     world.spawn().insert(Position(0.0)).insert(Velocity(i as f32));
+/// }
+///
+/// let mut query = world.query_mut();
+/// query
+///     .get_mut::<(&mut Position, &Velocity)>()
+///     .unwrap()
+///     .par_for_each(|(_, (position, velocity))| position.0 += velocity.0);
+///
+/// let mut query = world.query();
+/// let sum: f32 = query
+///     .get::<&Position>()
+///     .unwrap()
+///     .par_iter()
+///     .map(|(_, position)| position.0)
+///     .sum();
+/// assert_eq!(sum, 4950.0);
+/// ```
+pub trait ParallelQueryIter: Iterator + Sized
+where
+    Self::Item: Send,
+{
+    /// Collect the results into a `Vec`, iterated in parallel by rayon.
+    fn par_iter(self) -> IntoIter<Self::Item> {
+        self.collect::<Vec<_>>().into_par_iter()
+    }
+
+    /// Run `f` on each of the results in parallel.
+    fn par_for_each<F: Fn(Self::Item) + Sync + Send>(self, f: F) {
+        self.par_iter().for_each(f);
+    }
+}
+
+impl<T: Iterator> ParallelQueryIter for T where T::Item: Send {}
+
+#[cfg(all(test, feature = "slab"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn parallel_query_iteration() {
+        #[derive(Debug, PartialEq)]
+        struct Position(u64);
+        struct Velocity(u64);
+        struct Frozen;
+
+        fn movement(query: Query<usize, (&mut Position, &Velocity), Without<Frozen>>) {
+            query.par_for_each(|(_, (position, velocity))| position.0 += velocity.0);
+        }
+
+        let mut world = World::new();
+        for i in 0..1000 {
+            world.spawn().insert(Position(0)).insert(Velocity(i));
+        }
+        let frozen = world
+            .spawn()
+            .insert(Position(0))
+            .insert(Velocity(1))
+            .insert(Frozen)
+            .id;
+
+        let mut query = world.query_mut();
+        query
+            .without::<Frozen>()
+            .get_mut::<(&mut Position, &Velocity)>()
+            .unwrap()
+            .par_for_each(|(_, (position, velocity))| position.0 += velocity.0);
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(Stage::Update, movement);
+        schedule.run(&mut world);
+
+        let mut query = world.query();
+        let sum = query
+            .get::<&Position>()
+            .unwrap()
+            .par_iter()
+            .map(|(_, position)| position.0)
+            .sum::<u64>();
+        assert_eq!(sum, 2 * (0..1000).sum::<u64>());
+        assert_eq!(world.component::<Position>(frozen), Some(&Position(0)));
+    }
+}
@@ -1,19 +1,23 @@
+use core::any::TypeId;
 use std::collections::{HashMap, HashSet};
 
 use crate::{
+    query_data::QueryData,
     storage::{Identifier, Storage},
     type_map::TypeMap,
-    world::{ComponentId, Table, World},
+    world::{ComponentId, ComponentTicks, EntityTypeMap, World, WorldValue},
 };
 
 #[derive(Debug)]
-pub struct QueryBuilder<'a, I: Identifier, E: Storage<Key = I, Value = TypeMap>> {
+pub struct QueryBuilder<'a, I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>> {
     pub with_call_count: u32,
     pub entity_freq: EntityFrequency<I>,
     pub world: &'a World<I, E>,
 }
 
-impl<'a, I: Identifier, E: Storage<Key = I, Value = TypeMap>> QueryBuilder<'a, I, E> {
+impl<'a, I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>>
+    QueryBuilder<'a, I, E>
+{
     pub fn new(world: &'a World<I, E>) -> Self {
         Self {
             world,
@@ -25,7 +29,8 @@ impl<'a, I: Identifier, E: Storage<Key = I, Value = TypeMap>> QueryBuilder<'a, I
 
 pub trait QueryBuilderMethods<'a> {
     type Key: Identifier + 'static;
-    type EntityStorage: Storage<Key = Self::Key, Value = TypeMap>;
+    type Erased: ?Sized + WorldValue;
+    type EntityStorage: Storage<Key = Self::Key, Value = TypeMap<Self::Erased>>;
     type ComponentStorage<T: 'static>: Storage<Key = Self::Key, Value = T> + 'static;
 
     fn create(world: &'a World<Self::Key, Self::EntityStorage>) -> Self;
@@ -38,81 +43,95 @@ pub trait QueryBuilderMethods<'a> {
 
     fn world(&self) -> &World<Self::Key, Self::EntityStorage>;
 
+    /// Filter entities to ones having component `C`.
+    ///
+    /// Filtering on a component that was never inserted matches nothing.
     fn with<C: 'static>(&mut self) -> &mut Self {
-        let world = self.world();
-        let Some(table) = world
-            .all_tables
-            .0
-            .get::<Table<C, Self::Key, Self::ComponentStorage<C>>>()
-        else {
-            return self;
-        };
+        let filter = Filter::with::<C, _, _>(self.world());
+        *self.with_call_count() += self.entity_freq_mut().apply(filter);
 
-        let entities_iter = world
-            .entities
-            .0
-            .iter()
-            .filter_map(|(entity_id, component_ids)| {
-                let comp_id = component_ids.get::<ComponentId<C, Self::Key>>()?;
-                Some((entity_id, comp_id))
-            });
-
-        let mut entity_ids = Vec::new();
-        for (entity_id, comp_id) in entities_iter {
-            let Some(_component) = table.storage.get(comp_id.id) else {
-                continue;
-            };
-            // Collect ids into temp vec for freq analysis...
-            entity_ids.push(entity_id);
-        }
-        // Add collected ids into freq tracker...
-        entity_ids
-            .iter()
-            .for_each(|entity_id| self.entity_freq_mut().add_entity(*entity_id));
+        self
+    }
+
+    /// Filter entities to ones having any of the components in the set `S`.
+    ///
+    /// Counts as a single `with` filter, eg. `any_of::<(A, B)>()` matches `A` or `B`.
+    fn any_of<S: ComponentSet>(&mut self) -> &mut Self {
+        let filter = Filter::any_of::<S, _, _>(self.world());
+        *self.with_call_count() += self.entity_freq_mut().apply(filter);
+
+        self
+    }
 
-        *self.with_call_count() += 1;
+    /// Filter out entities having component `C`.
+    fn without<C: 'static>(&mut self) -> &mut Self {
+        let filter = Filter::without::<C, _, _>(self.world());
+        *self.with_call_count() += self.entity_freq_mut().apply(filter);
 
         self
     }
 
-    fn get<C: 'static>(&'a mut self) -> Option<impl Iterator<Item = (Self::Key, &'a C)>> {
+    /// Filter entities to ones having component `C` inserted since the last
+    /// [`WorldMethods::clear_trackers`](crate::prelude::WorldMethods::clear_trackers).
+    ///
+    /// The baseline is global, ie. shared by all builder queries. Use
+    /// [`Self::added_since`] to track changes independently.
+    fn added<C: 'static>(&mut self) -> &mut Self {
+        let last_change_tick = self.world().ticks.last_change_tick;
+        self.added_since::<C>(last_change_tick)
+    }
+
+    /// Filter entities to ones having component `C` inserted or mutably accessed since the last
+    /// [`WorldMethods::clear_trackers`](crate::prelude::WorldMethods::clear_trackers).
+    ///
+    /// The baseline is global, ie. shared by all builder queries. Use
+    /// [`Self::changed_since`] to track changes independently.
+    fn changed<C: 'static>(&mut self) -> &mut Self {
+        let last_change_tick = self.world().ticks.last_change_tick;
+        self.changed_since::<C>(last_change_tick)
+    }
+
+    /// Filter entities to ones having component `C` inserted after the `last_run` tick, kept by
+    /// the caller from
+    /// [`WorldMethods::advance_change_tick`](crate::prelude::WorldMethods::advance_change_tick).
+    fn added_since<C: 'static>(&mut self, last_run: u32) -> &mut Self {
+        let filter = Filter::added_since::<C, _, _>(self.world(), last_run);
+        *self.with_call_count() += self.entity_freq_mut().apply(filter);
+
+        self
+    }
+
+    /// Filter entities to ones having component `C` inserted or mutably accessed after the
+    /// `last_run` tick, kept by the caller from
+    /// [`WorldMethods::advance_change_tick`](crate::prelude::WorldMethods::advance_change_tick).
+    fn changed_since<C: 'static>(&mut self, last_run: u32) -> &mut Self {
+        let filter = Filter::changed_since::<C, _, _>(self.world(), last_run);
+        *self.with_call_count() += self.entity_freq_mut().apply(filter);
+
+        self
+    }
+
+    /// Get components of all entities matching the filters (all entities if none).
+    ///
+    /// Returns `None` if a fetched component was never inserted.
+    fn get<Q: QueryData>(&'a mut self) -> Option<impl Iterator<Item = (Self::Key, Q::Item<'a>)>> {
         let with_call_count = *self.with_call_count();
         let world = self.world();
-        let table = world
-            .all_tables
-            .0
-            .get::<Table<C, Self::Key, Self::ComponentStorage<C>>>()?;
-        let filtered_entities = self
-            .entity_freq()
-            .freq
-            .iter()
-            .filter_map(|(id, freq)| {
-                if *freq == with_call_count {
-                    Some(*id)
-                } else {
-                    None
-                }
-            })
-            .collect::<HashSet<Self::Key>>();
-
-        let entities_with_comp_ids =
+        let fetch = Q::init_fetch(world)?;
+        let filtered_entities = self.entity_freq().matching(world, with_call_count);
+
+        let entities_with_comp_values =
             world
                 .entities
                 .0
                 .iter()
                 .filter_map(move |(entity_id, component_ids)| {
-                    let comp_id = component_ids.get::<ComponentId<C, Self::Key>>()?;
-                    if filtered_entities.contains(&entity_id) {
-                        Some((entity_id, comp_id))
-                    } else {
-                        None
+                    if !filtered_entities.contains(&entity_id) {
+                        return None;
                     }
+                    let item = Q::fetch::<Self::Key, Self::Erased>(&fetch, component_ids)?;
+                    Some((entity_id, item))
                 });
-        let entities_with_comp_values =
-            entities_with_comp_ids.filter_map(|(entity_id, comp_id)| {
-                let component = table.storage.get(comp_id.id)?;
-                Some((entity_id, component))
-            });
         Some(entities_with_comp_values)
     }
 }
@@ -120,12 +139,15 @@ pub trait QueryBuilderMethods<'a> {
 #[derive(Debug, Default)]
 pub struct EntityFrequency<I: Identifier> {
     pub(crate) freq: HashMap<I, u32>,
+    /// Entities excluded by `without` filters.
+    pub(crate) excluded: HashSet<I>,
 }
 
 impl<I: Identifier> EntityFrequency<I> {
     pub fn new() -> Self {
         Self {
             freq: HashMap::new(),
+            excluded: HashSet::new(),
         }
     }
 
@@ -138,4 +160,236 @@ impl<I: Identifier> EntityFrequency<I> {
         };
         *freq += 1;
     }
+
+    pub fn exclude_entity(&mut self, id: I) {
+        self.excluded.insert(id);
+    }
+
+    /// Check if entity is matched by all `with` filters, ie. added `with_call_count` times,
+    /// and not excluded by any `without` filter.
+    ///
+    /// With no `with` filters (`with_call_count = 0`) all non-excluded entities match.
+    pub fn is_match(&self, id: I, with_call_count: u32) -> bool {
+        let freq = self.freq.get(&id).copied().unwrap_or(0);
+        freq == with_call_count && !self.excluded.contains(&id)
+    }
+
+    /// Apply the filter, returns the number of `with` filters it counts as (`0` for `without`).
+    pub(crate) fn apply(&mut self, filter: Filter<I>) -> u32 {
+        match filter {
+            Filter::With(entity_ids) => {
+                // Add collected ids into freq tracker...
+                entity_ids
+                    .into_iter()
+                    .for_each(|entity_id| self.add_entity(entity_id));
+                1
+            }
+            Filter::Without(entity_ids) => {
+                entity_ids
+                    .into_iter()
+                    .for_each(|entity_id| self.exclude_entity(entity_id));
+                0
+            }
+        }
+    }
+
+    /// Ids of the world's entities that are a match, see [`EntityFrequency::is_match`].
+    pub(crate) fn matching<E: Storage<Key = I, Value: EntityTypeMap>>(
+        &self,
+        world: &World<I, E>,
+        with_call_count: u32,
+    ) -> HashSet<I> {
+        world
+            .entities
+            .0
+            .iter()
+            .map(|(entity_id, _)| entity_id)
+            .filter(|entity_id| self.is_match(*entity_id, with_call_count))
+            .collect()
+    }
+}
+
+/// Filter of the query builders (shared by [`QueryBuilderMethods`] and
+/// [`QueryMutBuilderMethods`](crate::prelude::QueryMutBuilderMethods)), resolved to the ids of
+/// the entities it applies to, see [`EntityFrequency::apply`].
+pub(crate) enum Filter<I> {
+    /// Entities to match, all of them by a single `with` like filter.
+    With(Vec<I>),
+    /// Entities to exclude.
+    Without(Vec<I>),
 }
+
+impl<I: Identifier + 'static> Filter<I> {
+    /// Entities having component `C`.
+    pub(crate) fn with<C, E, V>(world: &World<I, E>) -> Self
+    where
+        C: 'static,
+        E: Storage<Key = I, Value = TypeMap<V>>,
+        V: ?Sized + WorldValue,
+    {
+        Self::With(entities_with::<C, I, E, V>(world))
+    }
+
+    /// Entities having any of the components in the set `S`.
+    pub(crate) fn any_of<S, E, V>(world: &World<I, E>) -> Self
+    where
+        S: ComponentSet,
+        E: Storage<Key = I, Value = TypeMap<V>>,
+        V: ?Sized + WorldValue,
+    {
+        Self::With(S::entities_with_any(world).into_iter().collect())
+    }
+
+    /// Entities having component `C`, to exclude.
+    pub(crate) fn without<C, E, V>(world: &World<I, E>) -> Self
+    where
+        C: 'static,
+        E: Storage<Key = I, Value = TypeMap<V>>,
+        V: ?Sized + WorldValue,
+    {
+        Self::Without(entities_with::<C, I, E, V>(world))
+    }
+
+    /// Entities having component `C` inserted after the `last_run` tick.
+    pub(crate) fn added_since<C, E, V>(world: &World<I, E>, last_run: u32) -> Self
+    where
+        C: 'static,
+        E: Storage<Key = I, Value = TypeMap<V>>,
+        V: ?Sized + WorldValue,
+    {
+        Self::With(entities_with_ticks::<C, I, E, V>(world, |ticks| {
+            ticks.added > last_run
+        }))
+    }
+
+    /// Entities having component `C` inserted or mutably accessed after the `last_run` tick.
+    pub(crate) fn changed_since<C, E, V>(world: &World<I, E>, last_run: u32) -> Self
+    where
+        C: 'static,
+        E: Storage<Key = I, Value = TypeMap<V>>,
+        V: ?Sized + WorldValue,
+    {
+        Self::With(entities_with_ticks::<C, I, E, V>(world, |ticks| {
+            ticks.changed > last_run
+        }))
+    }
+}
+
+/// Collect ids of entities having component `C`.
+///
+/// Empty if there is no table for `C`, ie. it was never inserted.
+fn entities_with<C, I, E, V>(world: &World<I, E>) -> Vec<I>
+where
+    C: 'static,
+    I: Identifier + 'static,
+    E: Storage<Key = I, Value = TypeMap<V>>,
+    V: ?Sized + WorldValue,
+{
+    let Some(table) = world
+        .all_tables
+        .get_erased(TypeId::of::<ComponentId<C, I>>())
+    else {
+        return Vec::new();
+    };
+    world
+        .entities
+        .0
+        .iter()
+        .filter_map(|(entity_id, component_ids)| {
+            let comp_id = component_ids.get::<ComponentId<C, I>>()?;
+            // Skip dangling ids not found in the table...
+            table.get_erased(comp_id.id)?;
+            Some(entity_id)
+        })
+        .collect()
+}
+
+/// Collect ids of entities having component `C` with ticks matching the predicate.
+fn entities_with_ticks<C, I, E, V>(
+    world: &World<I, E>,
+    predicate: impl Fn(&ComponentTicks) -> bool,
+) -> Vec<I>
+where
+    C: 'static,
+    I: Identifier + 'static,
+    E: Storage<Key = I, Value = TypeMap<V>>,
+    V: ?Sized + WorldValue,
+{
+    let Some(table) = world
+        .all_tables
+        .get_erased(TypeId::of::<ComponentId<C, I>>())
+    else {
+        return Vec::new();
+    };
+    world
+        .entities
+        .0
+        .iter()
+        .filter_map(|(entity_id, component_ids)| {
+            let comp_id = component_ids.get::<ComponentId<C, I>>()?;
+            predicate(table.ticks(comp_id.id)?).then_some(entity_id)
+        })
+        .collect()
+}
+
+/// Trait for a set of component types, used by `any_of` filters.
+///
+/// Implemented for tuples of component types (up to 8 elements).
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// struct Player(&'static str);
+/// struct Footballer;
+/// struct Cricketer;
+///
+/// let mut world = World::new();
+/// world.spawn().insert(Player("Mike")).insert(Footballer);
+/// world.spawn().insert(Player("Sam")).insert(Cricketer);
+/// world.spawn().insert(Player("Hannah"));
+///
+/// let mut query = world.query();
+/// let mut results = query
+///     .any_of::<(Footballer, Cricketer)>()
+///     .get::<&Player>()
+///     .unwrap()
+///     .map(|(_, player)| player.0);
+/// assert_eq!(results.next(), Some("Mike"));
+/// assert_eq!(results.next(), Some("Sam"));
+/// assert_eq!(results.next(), None);
+/// ```
+pub trait ComponentSet {
+    /// Collect ids of entities having any of the components in the set.
+    fn entities_with_any<I, E, V>(world: &World<I, E>) -> HashSet<I>
+    where
+        I: Identifier + 'static,
+        E: Storage<Key = I, Value = TypeMap<V>>,
+        V: ?Sized + WorldValue;
+}
+
+macro_rules! impl_component_set_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: 'static),+> ComponentSet for ($($name,)+) {
+            fn entities_with_any<I, E, V>(world: &World<I, E>) -> HashSet<I>
+            where
+                I: Identifier + 'static,
+                E: Storage<Key = I, Value = TypeMap<V>>,
+                V: ?Sized + WorldValue,
+            {
+                let mut entity_ids = HashSet::new();
+                $(entity_ids.extend(entities_with::<$name, I, E, V>(world));)+
+                entity_ids
+            }
+        }
+    };
+}
+
+impl_component_set_tuple!(C0);
+impl_component_set_tuple!(C0, C1);
+impl_component_set_tuple!(C0, C1, C2);
+impl_component_set_tuple!(C0, C1, C2, C3);
+impl_component_set_tuple!(C0, C1, C2, C3, C4);
+impl_component_set_tuple!(C0, C1, C2, C3, C4, C5);
+impl_component_set_tuple!(C0, C1, C2, C3, C4, C5, C6);
+impl_component_set_tuple!(C0, C1, C2, C3, C4, C5, C6, C7);
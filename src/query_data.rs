@@ -0,0 +1,551 @@
+use core::any::{Any, TypeId};
+use std::collections::HashMap;
+#[cfg(feature = "rayon")]
+use std::collections::HashSet;
+
+use crate::{
+    storage::{Identifier, Storage},
+    system::SystemAccess,
+    type_map::{AnyValue, TypeMap},
+    world::{AllTables, AnyTable, ComponentId, ComponentTicks, EntityTypeMap, World, WorldValue},
+};
+
+/// Trait for data that can be fetched per entity by a query.
+///
+/// Implemented for component refs `&C`, `Option<&C>` and tuples of them (up to 8 elements).
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// struct Player(&'static str);
+/// struct Age(u8);
+///
+/// let mut world = World::new();
+/// world.spawn().insert(Player("Mike")).insert(Age(30));
+/// world.spawn().insert(Player("Hannah"));
+///
+/// let mut query = world.query();
+/// let mut results = query
+///     .with::<Player>()
+///     .get::<(&Player, &Age)>()
+///     .unwrap()
+///     .map(|(_, (player, age))| (player.0, age.0));
+/// assert_eq!(results.next(), Some(("Mike", 30)));
+/// assert_eq!(results.next(), None);
+/// ```
+pub trait QueryData {
+    /// Item yielded for each matching entity.
+    type Item<'w>;
+    /// Tables resolved once per query, used to fetch [`QueryData::Item`]s.
+    type Fetch<'w, I: Identifier + 'static>;
+
+    /// Resolve the tables needed by the query.
+    ///
+    /// Returns `None` if the world has no table for a required component.
+    fn init_fetch<'w, I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>>(
+        world: &'w World<I, E>,
+    ) -> Option<Self::Fetch<'w, I>>;
+
+    /// Fetch the item using the entity's component ids.
+    ///
+    /// Returns `None` if the entity does not match.
+    fn fetch<'w, I: Identifier + 'static, V: ?Sized + AnyValue>(
+        fetch: &Self::Fetch<'w, I>,
+        entity: &'w TypeMap<V>,
+    ) -> Option<Self::Item<'w>>;
+}
+
+impl<C: 'static> QueryData for &C {
+    type Item<'w> = &'w C;
+    type Fetch<'w, I: Identifier + 'static> = &'w dyn AnyTable<I>;
+
+    fn init_fetch<'w, I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>>(
+        world: &'w World<I, E>,
+    ) -> Option<Self::Fetch<'w, I>> {
+        world
+            .all_tables
+            .get_erased(TypeId::of::<ComponentId<C, I>>())
+    }
+
+    fn fetch<'w, I: Identifier + 'static, V: ?Sized + AnyValue>(
+        fetch: &Self::Fetch<'w, I>,
+        entity: &'w TypeMap<V>,
+    ) -> Option<Self::Item<'w>> {
+        let table: &'w dyn AnyTable<I> = *fetch;
+        let comp_id = entity.get::<ComponentId<C, I>>()?;
+        table.get_erased(comp_id.id)?.downcast_ref::<C>()
+    }
+}
+
+/// Optional data, fetched as `None` for entities that do not have it.
+impl<Q: QueryData> QueryData for Option<Q> {
+    type Item<'w> = Option<Q::Item<'w>>;
+    type Fetch<'w, I: Identifier + 'static> = Option<Q::Fetch<'w, I>>;
+
+    fn init_fetch<'w, I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>>(
+        world: &'w World<I, E>,
+    ) -> Option<Self::Fetch<'w, I>> {
+        Some(Q::init_fetch(world))
+    }
+
+    fn fetch<'w, I: Identifier + 'static, V: ?Sized + AnyValue>(
+        fetch: &Self::Fetch<'w, I>,
+        entity: &'w TypeMap<V>,
+    ) -> Option<Self::Item<'w>> {
+        Some(fetch.as_ref().and_then(|fetch| Q::fetch(fetch, entity)))
+    }
+}
+
+/// Trait for data that can be fetched (mutably) per entity by a mutable query.
+///
+/// Implemented for `&mut C`, `&C`, `Option<_>` of them and tuples (up to 8 elements).
+///
+/// __Note: Accessing the same component type mutably more than once in a query panics.__
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// struct Position(f32);
+/// struct Velocity(f32);
+///
+/// let mut world = World::new();
+/// world.spawn().insert(Position(0.0)).insert(Velocity(1.0));
+/// world.spawn().insert(Position(5.0)).insert(Velocity(-1.0));
+///
+/// let mut query = world.query_mut();
+/// for (_, (position, velocity)) in query
+///     .with::<Position>()
+///     .get_mut::<(&mut Position, &Velocity)>()
+///     .unwrap()
+/// {
+///     position.0 += velocity.0;
+/// }
+///
+/// let mut query = world.query();
+/// let mut results = query.with::<Position>().get::<&Position>().unwrap();
+/// assert_eq!(results.next().unwrap().1.0, 1.0);
+/// assert_eq!(results.next().unwrap().1.0, 4.0);
+/// ```
+pub trait QueryDataMut {
+    /// Item yielded for each matching entity.
+    type Item<'w>;
+    /// Tables resolved once per query, used to fetch [`QueryDataMut::Item`]s.
+    type Fetch<'w, I: Identifier + 'static>;
+
+    /// Take the tables needed by the query.
+    ///
+    /// Returns `None` if the world has no table for a required component.
+    fn init_fetch<'w, I: Identifier + 'static, V: ?Sized + WorldValue>(
+        tables: &mut TablesMut<'w, I, V>,
+    ) -> Option<Self::Fetch<'w, I>>;
+
+    /// Fetch the item using the entity's component ids.
+    ///
+    /// Returns `None` if the entity does not match.
+    fn fetch<'w, I: Identifier + 'static, V: ?Sized + AnyValue>(
+        fetch: &mut Self::Fetch<'w, I>,
+        entity: &'w TypeMap<V>,
+    ) -> Option<Self::Item<'w>>;
+
+    /// Whether the entity has the components required by the query.
+    ///
+    /// Checked before fetching tuples, so that components of entities not matching are not
+    /// marked as changed.
+    fn matches<I: Identifier + 'static, V: ?Sized + AnyValue>(entity: &TypeMap<V>) -> bool;
+
+    /// Declare the components read and written by the query.
+    fn access<I: Identifier + 'static>(access: &mut SystemAccess);
+}
+
+impl<C: 'static> QueryDataMut for &C {
+    type Item<'w> = &'w C;
+    type Fetch<'w, I: Identifier + 'static> = &'w dyn AnyTable<I>;
+
+    fn init_fetch<'w, I: Identifier + 'static, V: ?Sized + WorldValue>(
+        tables: &mut TablesMut<'w, I, V>,
+    ) -> Option<Self::Fetch<'w, I>> {
+        tables.take_ref(TypeId::of::<ComponentId<C, I>>())
+    }
+
+    fn fetch<'w, I: Identifier + 'static, V: ?Sized + AnyValue>(
+        fetch: &mut Self::Fetch<'w, I>,
+        entity: &'w TypeMap<V>,
+    ) -> Option<Self::Item<'w>> {
+        <&C as QueryData>::fetch(fetch, entity)
+    }
+
+    fn matches<I: Identifier + 'static, V: ?Sized + AnyValue>(entity: &TypeMap<V>) -> bool {
+        entity.has::<ComponentId<C, I>>()
+    }
+
+    fn access<I: Identifier + 'static>(access: &mut SystemAccess) {
+        access.read_component::<C, I>();
+    }
+}
+
+/// Fetching marks the component as changed.
+impl<C: 'static> QueryDataMut for &mut C {
+    type Item<'w> = &'w mut C;
+    type Fetch<'w, I: Identifier + 'static> = ComponentsMut<'w, I, C>;
+
+    fn init_fetch<'w, I: Identifier + 'static, V: ?Sized + WorldValue>(
+        tables: &mut TablesMut<'w, I, V>,
+    ) -> Option<Self::Fetch<'w, I>> {
+        let change_tick = tables.change_tick;
+        let (components, ticks) = tables
+            .take(TypeId::of::<ComponentId<C, I>>())?
+            .split_mut_erased();
+        Some(ComponentsMut {
+            components,
+            skipped: HashMap::new(),
+            ticks,
+            change_tick,
+        })
+    }
+
+    fn fetch<'w, I: Identifier + 'static, V: ?Sized + AnyValue>(
+        fetch: &mut Self::Fetch<'w, I>,
+        entity: &'w TypeMap<V>,
+    ) -> Option<Self::Item<'w>> {
+        let comp_id = entity.get::<ComponentId<C, I>>()?;
+        fetch.get(comp_id.id)
+    }
+
+    fn matches<I: Identifier + 'static, V: ?Sized + AnyValue>(entity: &TypeMap<V>) -> bool {
+        entity.has::<ComponentId<C, I>>()
+    }
+
+    fn access<I: Identifier + 'static>(access: &mut SystemAccess) {
+        access.write_component::<C, I>();
+    }
+}
+
+/// Optional data, fetched as `None` for entities that do not have it.
+impl<Q: QueryDataMut> QueryDataMut for Option<Q> {
+    type Item<'w> = Option<Q::Item<'w>>;
+    type Fetch<'w, I: Identifier + 'static> = Option<Q::Fetch<'w, I>>;
+
+    fn init_fetch<'w, I: Identifier + 'static, V: ?Sized + WorldValue>(
+        tables: &mut TablesMut<'w, I, V>,
+    ) -> Option<Self::Fetch<'w, I>> {
+        Some(Q::init_fetch(tables))
+    }
+
+    fn fetch<'w, I: Identifier + 'static, V: ?Sized + AnyValue>(
+        fetch: &mut Self::Fetch<'w, I>,
+        entity: &'w TypeMap<V>,
+    ) -> Option<Self::Item<'w>> {
+        Some(fetch.as_mut().and_then(|fetch| Q::fetch(fetch, entity)))
+    }
+
+    fn matches<I: Identifier + 'static, V: ?Sized + AnyValue>(_entity: &TypeMap<V>) -> bool {
+        true
+    }
+
+    fn access<I: Identifier + 'static>(access: &mut SystemAccess) {
+        Q::access::<I>(access);
+    }
+}
+
+/// Components of a table taken mutably by a query, each handed out once.
+///
+/// The table is walked only as far as needed to reach a component, keeping the ones walked past
+/// for later lookups. Hence fetching in table order (eg. for entities spawned in order) does not
+/// allocate.
+pub struct ComponentsMut<'w, I: Identifier, C> {
+    /// Components not yet walked past.
+    components: Box<dyn Iterator<Item = (I, &'w mut dyn Any)> + 'w>,
+    /// Components walked past, keyed by their component key.
+    skipped: HashMap<I, &'w mut C>,
+    ticks: &'w mut HashMap<I, ComponentTicks>,
+    /// Tick stamped on the components handed out.
+    change_tick: u32,
+}
+
+impl<'w, I: Identifier, C: 'static> ComponentsMut<'w, I, C> {
+    /// Take the component for the given component key, marking it as changed.
+    fn get(&mut self, id: I) -> Option<&'w mut C> {
+        let component = match self.skipped.remove(&id) {
+            Some(component) => component,
+            None => loop {
+                let (key, component) = self.components.next()?;
+                let component = component.downcast_mut::<C>()?;
+                if key == id {
+                    break component;
+                }
+                self.skipped.insert(key, component);
+            },
+        };
+        if let Some(ticks) = self.ticks.get_mut(&id) {
+            ticks.changed = self.change_tick;
+        }
+
+        Some(component)
+    }
+}
+
+/// Type-erased tables of a world, each of which can be taken mutably once, or immutably any
+/// number of times, by a query.
+pub struct TablesMut<'w, I: Identifier + 'static, V: ?Sized + WorldValue = dyn Any> {
+    tables: HashMap<TypeId, Access<'w, V::Table<I>>>,
+    /// Tick stamped on components accessed mutably.
+    change_tick: u32,
+}
+
+impl<'w, I: Identifier + 'static, V: ?Sized + WorldValue> TablesMut<'w, I, V> {
+    pub(crate) fn new(all_tables: &'w mut AllTables<I, V>, change_tick: u32) -> Self {
+        Self {
+            tables: all_tables
+                .0
+                .iter_mut()
+                .map(|(type_id, table)| (*type_id, Access::Unique(&mut **table)))
+                .collect(),
+            change_tick,
+        }
+    }
+
+    /// Split off the tables of the components in `access`, for a system run at `change_tick`.
+    ///
+    /// Panics if any of the tables were already taken mutably.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn split(&mut self, access: &SystemAccess, change_tick: u32) -> Self {
+        Self {
+            tables: split_accesses(
+                &mut self.tables,
+                access.component_reads(),
+                access.component_writes(),
+            ),
+            change_tick,
+        }
+    }
+
+    /// Peek at table using the `TypeId` of a [`ComponentId`], without taking it.
+    ///
+    /// Panics if the table was already taken mutably.
+    pub(crate) fn peek(&self, component_id_type: TypeId) -> Option<&dyn AnyTable<I>> {
+        self.tables.get(&component_id_type).map(|access| {
+            V::table_ref(
+                access
+                    .peek()
+                    .expect("Component accessed more than once in a mutable query"),
+            )
+        })
+    }
+
+    /// Take table (mutably) using the `TypeId` of a [`ComponentId`].
+    ///
+    /// Panics if the table was already taken.
+    fn take(&mut self, component_id_type: TypeId) -> Option<&'w mut dyn AnyTable<I>> {
+        self.tables.get_mut(&component_id_type).map(|access| {
+            V::table_mut(
+                access
+                    .take_mut()
+                    .expect("Component accessed more than once in a mutable query"),
+            )
+        })
+    }
+
+    /// Take table (immutably) using the `TypeId` of a [`ComponentId`].
+    ///
+    /// Panics if the table was already taken mutably.
+    fn take_ref(&mut self, component_id_type: TypeId) -> Option<&'w dyn AnyTable<I>> {
+        self.tables.get_mut(&component_id_type).map(|access| {
+            V::table_ref(
+                access
+                    .take_ref()
+                    .expect("Component accessed more than once in a mutable query"),
+            )
+        })
+    }
+}
+
+/// Borrow of a value, which can be handed out mutably once or immutably any number of times.
+pub(crate) enum Access<'w, T: ?Sized> {
+    Unique(&'w mut T),
+    Shared(&'w T),
+    Taken,
+}
+
+impl<'w, T: ?Sized> Access<'w, T> {
+    /// Take the mutable borrow, returns `None` if it was already handed out.
+    pub(crate) fn take_mut(&mut self) -> Option<&'w mut T> {
+        match core::mem::replace(self, Self::Taken) {
+            Self::Unique(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Reborrow without taking, returns `None` if it was already handed out mutably.
+    pub(crate) fn peek(&self) -> Option<&T> {
+        match self {
+            Self::Unique(value) => Some(&**value),
+            Self::Shared(value) => Some(*value),
+            Self::Taken => None,
+        }
+    }
+
+    /// Take a shared borrow, returns `None` if it was already handed out mutably.
+    pub(crate) fn take_ref(&mut self) -> Option<&'w T> {
+        let value: &'w T = match core::mem::replace(self, Self::Taken) {
+            Self::Unique(value) => value,
+            Self::Shared(value) => value,
+            Self::Taken => return None,
+        };
+        *self = Self::Shared(value);
+
+        Some(value)
+    }
+}
+
+/// Split off the values in `reads` (shared) and `writes` (unique) out of `accesses`.
+///
+/// Panics if any of them were already handed out mutably.
+#[cfg(feature = "rayon")]
+pub(crate) fn split_accesses<'w, T: ?Sized>(
+    accesses: &mut HashMap<TypeId, Access<'w, T>>,
+    reads: &HashSet<TypeId>,
+    writes: &HashSet<TypeId>,
+) -> HashMap<TypeId, Access<'w, T>> {
+    let mut split = HashMap::new();
+    for type_id in writes {
+        if let Some(access) = accesses.get_mut(type_id) {
+            let value = access
+                .take_mut()
+                .expect("Accessed by more than one system in parallel");
+            split.insert(*type_id, Access::Unique(value));
+        }
+    }
+    for type_id in reads.difference(writes) {
+        if let Some(access) = accesses.get_mut(type_id) {
+            let value = access
+                .take_ref()
+                .expect("Accessed mutably by another system in parallel");
+            split.insert(*type_id, Access::Shared(value));
+        }
+    }
+
+    split
+}
+
+macro_rules! impl_query_data_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: QueryData),+> QueryData for ($($name,)+) {
+            type Item<'w> = ($($name::Item<'w>,)+);
+            type Fetch<'w, I: Identifier + 'static> = ($($name::Fetch<'w, I>,)+);
+
+            fn init_fetch<'w, I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>>(
+                world: &'w World<I, E>,
+            ) -> Option<Self::Fetch<'w, I>> {
+                Some(($($name::init_fetch(world)?,)+))
+            }
+
+            #[allow(non_snake_case)]
+            fn fetch<'w, I: Identifier + 'static, V: ?Sized + AnyValue>(
+                fetch: &Self::Fetch<'w, I>,
+                entity: &'w TypeMap<V>,
+            ) -> Option<Self::Item<'w>> {
+                let ($($name,)+) = fetch;
+                Some(($($name::fetch::<I, V>($name, entity)?,)+))
+            }
+        }
+
+        impl<$($name: QueryDataMut),+> QueryDataMut for ($($name,)+) {
+            type Item<'w> = ($($name::Item<'w>,)+);
+            type Fetch<'w, I: Identifier + 'static> = ($($name::Fetch<'w, I>,)+);
+
+            fn init_fetch<'w, I: Identifier + 'static, V: ?Sized + WorldValue>(
+                tables: &mut TablesMut<'w, I, V>,
+            ) -> Option<Self::Fetch<'w, I>> {
+                Some(($($name::init_fetch(tables)?,)+))
+            }
+
+            #[allow(non_snake_case)]
+            fn fetch<'w, I: Identifier + 'static, V: ?Sized + AnyValue>(
+                fetch: &mut Self::Fetch<'w, I>,
+                entity: &'w TypeMap<V>,
+            ) -> Option<Self::Item<'w>> {
+                // Don't mark components changed by fetching them, unless all of them match...
+                if !Self::matches::<I, V>(entity) {
+                    return None;
+                }
+                let ($($name,)+) = fetch;
+                Some(($($name::fetch::<I, V>($name, entity)?,)+))
+            }
+
+            fn matches<I: Identifier + 'static, V: ?Sized + AnyValue>(entity: &TypeMap<V>) -> bool {
+                $($name::matches::<I, V>(entity))&&+
+            }
+
+            fn access<I: Identifier + 'static>(access: &mut SystemAccess) {
+                $($name::access::<I>(access);)+
+            }
+        }
+    };
+}
+
+impl_query_data_tuple!(Q0);
+impl_query_data_tuple!(Q0, Q1);
+impl_query_data_tuple!(Q0, Q1, Q2);
+impl_query_data_tuple!(Q0, Q1, Q2, Q3);
+impl_query_data_tuple!(Q0, Q1, Q2, Q3, Q4);
+impl_query_data_tuple!(Q0, Q1, Q2, Q3, Q4, Q5);
+impl_query_data_tuple!(Q0, Q1, Q2, Q3, Q4, Q5, Q6);
+impl_query_data_tuple!(Q0, Q1, Q2, Q3, Q4, Q5, Q6, Q7);
+
+#[cfg(all(test, feature = "slab"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn partial_matches_are_not_marked_changed() {
+        struct Position(i32);
+        struct Velocity(i32);
+        #[derive(Debug, Default, PartialEq)]
+        struct Changes(Vec<usize>);
+
+        fn movement(query: Query<usize, (&mut Position, &Velocity)>) {
+            query.for_each(|(_, (position, velocity))| position.0 += velocity.0);
+        }
+
+        fn track(query: Query<usize, &Position, Changed<Position>>, mut changes: ResMut<Changes>) {
+            changes.0 = query.map(|(entity, _)| entity).collect();
+        }
+
+        let mut world = World::new();
+        let a = world.spawn().insert(Position(0)).insert(Velocity(1)).id;
+        world.spawn().insert(Position(0));
+        world.clear_trackers();
+
+        // Entities without `Velocity` are skipped, regardless of the order of the tuple...
+        let mut query = world.query_mut();
+        query
+            .get_mut::<(&mut Position, &Velocity)>()
+            .unwrap()
+            .for_each(|(_, (position, velocity))| position.0 += velocity.0);
+        let mut query = world.query_mut();
+        query
+            .get_mut::<(&Velocity, &mut Position)>()
+            .unwrap()
+            .for_each(|(_, (velocity, position))| position.0 += velocity.0);
+        let mut query = world.query();
+        let changed = query
+            .changed::<Position>()
+            .get::<&Position>()
+            .unwrap()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        assert_eq!(changed, vec![a]);
+
+        // Systems see everything as changed on their first run...
+        world.insert_resource(Changes::default());
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(Stage::Update, movement)
+            .add_system(Stage::PostUpdate, track);
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Changes>(), Some(&Changes(vec![a])));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    query_builder::{ComponentSet, EntityFrequency, Filter},
+    query_data::{QueryDataMut, TablesMut},
+    storage::{Identifier, Storage},
+    type_map::TypeMap,
+    world::{EntityTypeMap, World, WorldValue},
+};
+
+#[derive(Debug)]
+pub struct QueryMutBuilder<'a, I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>> {
+    pub with_call_count: u32,
+    pub entity_freq: EntityFrequency<I>,
+    pub world: &'a mut World<I, E>,
+}
+
+impl<'a, I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>>
+    QueryMutBuilder<'a, I, E>
+{
+    pub fn new(world: &'a mut World<I, E>) -> Self {
+        Self {
+            world,
+            with_call_count: 0,
+            entity_freq: EntityFrequency::new(),
+        }
+    }
+}
+
+pub trait QueryMutBuilderMethods<'a> {
+    type Key: Identifier + 'static;
+    type Erased: ?Sized + WorldValue;
+    type EntityStorage: Storage<Key = Self::Key, Value = TypeMap<Self::Erased>>;
+    type ComponentStorage<T: 'static>: Storage<Key = Self::Key, Value = T> + 'static;
+
+    fn create(world: &'a mut World<Self::Key, Self::EntityStorage>) -> Self;
+
+    fn with_call_count(&mut self) -> &mut u32;
+
+    fn entity_freq(&self) -> &EntityFrequency<Self::Key>;
+
+    fn entity_freq_mut(&mut self) -> &mut EntityFrequency<Self::Key>;
+
+    fn world(&self) -> &World<Self::Key, Self::EntityStorage>;
+
+    fn world_mut(&mut self) -> &mut World<Self::Key, Self::EntityStorage>;
+
+    /// Filter entities to ones having component `C`.
+    ///
+    /// Filtering on a component that was never inserted matches nothing.
+    fn with<C: 'static>(&mut self) -> &mut Self {
+        let filter = Filter::with::<C, _, _>(self.world());
+        *self.with_call_count() += self.entity_freq_mut().apply(filter);
+
+        self
+    }
+
+    /// Filter entities to ones having any of the components in the set `S`.
+    ///
+    /// Counts as a single `with` filter, eg. `any_of::<(A, B)>()` matches `A` or `B`.
+    fn any_of<S: ComponentSet>(&mut self) -> &mut Self {
+        let filter = Filter::any_of::<S, _, _>(self.world());
+        *self.with_call_count() += self.entity_freq_mut().apply(filter);
+
+        self
+    }
+
+    /// Filter out entities having component `C`.
+    fn without<C: 'static>(&mut self) -> &mut Self {
+        let filter = Filter::without::<C, _, _>(self.world());
+        *self.with_call_count() += self.entity_freq_mut().apply(filter);
+
+        self
+    }
+
+    /// Filter entities to ones having component `C` inserted since the last
+    /// [`WorldMethods::clear_trackers`](crate::prelude::WorldMethods::clear_trackers).
+    ///
+    /// The baseline is global, ie. shared by all builder queries. Use
+    /// [`Self::added_since`] to track changes independently.
+    fn added<C: 'static>(&mut self) -> &mut Self {
+        let last_change_tick = self.world().ticks.last_change_tick;
+        self.added_since::<C>(last_change_tick)
+    }
+
+    /// Filter entities to ones having component `C` inserted or mutably accessed since the last
+    /// [`WorldMethods::clear_trackers`](crate::prelude::WorldMethods::clear_trackers).
+    ///
+    /// The baseline is global, ie. shared by all builder queries. Use
+    /// [`Self::changed_since`] to track changes independently.
+    fn changed<C: 'static>(&mut self) -> &mut Self {
+        let last_change_tick = self.world().ticks.last_change_tick;
+        self.changed_since::<C>(last_change_tick)
+    }
+
+    /// Filter entities to ones having component `C` inserted after the `last_run` tick, kept by
+    /// the caller from
+    /// [`WorldMethods::advance_change_tick`](crate::prelude::WorldMethods::advance_change_tick).
+    fn added_since<C: 'static>(&mut self, last_run: u32) -> &mut Self {
+        let filter = Filter::added_since::<C, _, _>(self.world(), last_run);
+        *self.with_call_count() += self.entity_freq_mut().apply(filter);
+
+        self
+    }
+
+    /// Filter entities to ones having component `C` inserted or mutably accessed after the
+    /// `last_run` tick, kept by the caller from
+    /// [`WorldMethods::advance_change_tick`](crate::prelude::WorldMethods::advance_change_tick).
+    fn changed_since<C: 'static>(&mut self, last_run: u32) -> &mut Self {
+        let filter = Filter::changed_since::<C, _, _>(self.world(), last_run);
+        *self.with_call_count() += self.entity_freq_mut().apply(filter);
+
+        self
+    }
+
+    /// Get components of all entities matching the filters (all entities if none).
+    ///
+    /// Returns `None` if a fetched component was never inserted.
+    fn get_mut<Q: QueryDataMut>(
+        &'a mut self,
+    ) -> Option<impl Iterator<Item = (Self::Key, Q::Item<'a>)>> {
+        let with_call_count = *self.with_call_count();
+        let filtered_entities = self.entity_freq().matching(self.world(), with_call_count);
+        let world = self.world_mut();
+        let change_tick = world.ticks.change_tick;
+        let mut fetch = Q::init_fetch(&mut TablesMut::new(&mut world.all_tables, change_tick))?;
+
+        let entities_with_comp_values =
+            world
+                .entities
+                .0
+                .iter()
+                .filter_map(move |(entity_id, component_ids)| {
+                    if !filtered_entities.contains(&entity_id) {
+                        return None;
+                    }
+                    let item = Q::fetch::<Self::Key, Self::Erased>(&mut fetch, component_ids)?;
+                    Some((entity_id, item))
+                });
+        Some(entities_with_comp_values)
+    }
+}
@@ -0,0 +1,478 @@
+use core::{
+    any::{Any, TypeId, type_name},
+    fmt::Debug,
+    marker::PhantomData,
+};
+use std::collections::HashMap;
+
+use crate::{
+    entity_builder::EntityBuilderMethods,
+    storage::Storage,
+    type_map::{ThreadSafe, TypeMap},
+    world::WorldMethods,
+};
+
+/// Format the component of the entity.
+type DebugFn<W> = fn(&W, <W as WorldMethods>::Key) -> Option<String>;
+
+/// Clone the component of the source entity into the target entity.
+type CloneFn<W> = fn(&mut W, <W as WorldMethods>::Key, <W as WorldMethods>::Key) -> bool;
+
+/// Insert the (boxed) component into the entity, handing it back if the entity does not exist.
+type InsertFn<W> = fn(&mut W, <W as WorldMethods>::Key, Box<dyn Any>) -> Result<(), Box<dyn Any>>;
+
+/// Insert the default component into the entity.
+type DefaultFn<W> = fn(&mut W, <W as WorldMethods>::Key) -> bool;
+
+/// Deserialize a (boxed) component.
+#[cfg(feature = "serde")]
+pub(crate) type DeserializeFn =
+    fn(&mut dyn erased_serde::Deserializer) -> Result<Box<dyn Any>, erased_serde::Error>;
+
+/// Info and type-erased hooks of a component registered on a [`ComponentRegistry`].
+pub struct ComponentInfo<W: WorldMethods> {
+    /// Stable name of the component, eg. used as its key when serialized.
+    name: &'static str,
+    type_id: TypeId,
+    type_name: &'static str,
+    size: usize,
+    insert: InsertFn<W>,
+    debug: Option<DebugFn<W>>,
+    clone: Option<CloneFn<W>>,
+    default: Option<DefaultFn<W>>,
+    #[cfg(feature = "serde")]
+    pub(crate) serialize: Option<fn(&dyn Any) -> &dyn erased_serde::Serialize>,
+    #[cfg(feature = "serde")]
+    pub(crate) deserialize: Option<DeserializeFn>,
+}
+
+impl<W: WorldMethods> ComponentInfo<W> {
+    /// Stable name of the component.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// `TypeId` of the component.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Rust type name of the component, see [`core::any::type_name`].
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Size of the component in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Insert the (boxed) component into the entity, replacing the existing one (if any).
+    ///
+    /// Returns the passed component back as `Err` if the entity does not exist. Panics if the
+    /// component is not of the registered type.
+    pub fn insert_boxed(
+        &self,
+        world: &mut W,
+        entity: W::Key,
+        component: Box<dyn Any>,
+    ) -> Result<(), Box<dyn Any>> {
+        (self.insert)(world, entity, component)
+    }
+
+    /// Format the component of the entity via its `Debug` hook.
+    ///
+    /// Returns `None` if there is no hook or the entity does not have the component.
+    pub fn debug(&self, world: &W, entity: W::Key) -> Option<String> {
+        self.debug?(world, entity)
+    }
+
+    /// Clone the component of the `source` entity into the `target` entity via its `Clone` hook.
+    ///
+    /// Returns `false` if there is no hook, the source does not have the component or the target
+    /// does not exist.
+    pub fn clone_component(&self, world: &mut W, source: W::Key, target: W::Key) -> bool {
+        self.clone.is_some_and(|clone| clone(world, source, target))
+    }
+
+    /// Insert the default component into the entity via its `Default` hook.
+    ///
+    /// Returns `false` if there is no hook or the entity does not exist.
+    pub fn insert_default(&self, world: &mut W, entity: W::Key) -> bool {
+        self.default.is_some_and(|default| default(world, entity))
+    }
+}
+
+impl<W: WorldMethods> Debug for ComponentInfo<W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ComponentInfo")
+            .field("name", &self.name)
+            .field("type_name", &self.type_name)
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Registry of components by `TypeId` and stable name, for worlds of type `W`.
+///
+/// Components are type-erased in the world, so the registry allows listing and operating on them
+/// generically (eg. for tooling, inspection or serialization) via the hooks added on register.
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// #[derive(Debug, Clone, Default, PartialEq)]
+/// struct Position(i32, i32);
+/// struct Handle;
+///
+/// let mut world = World::new();
+/// let a = world.spawn().insert(Position(1, 2)).insert(Handle).id;
+/// let b = world.spawn().id;
+///
+/// let mut registry = ComponentRegistry::new();
+/// registry
+///     .register::<Position>("Position")
+///     .with_debug()
+///     .with_clone()
+///     .with_default();
+/// registry.register::<Handle>("Handle");
+///
+/// let names = registry.components(&world, a).iter().map(|info| info.name()).collect::<Vec<_>>();
+/// assert_eq!(names, vec!["Position", "Handle"]);
+///
+/// let position = registry.get::<Position>().unwrap();
+/// assert_eq!(position.debug(&world, a).unwrap(), "Position(1, 2)");
+/// assert!(position.clone_component(&mut world, a, b));
+/// assert_eq!(world.component::<Position>(b), Some(&Position(1, 2)));
+/// ```
+pub struct ComponentRegistry<W: WorldMethods> {
+    infos: Vec<ComponentInfo<W>>,
+    /// Index of infos by component `TypeId`.
+    by_type_id: HashMap<TypeId, usize>,
+    /// Index of infos by name.
+    by_name: HashMap<&'static str, usize>,
+}
+
+impl<W: WorldMethods> ComponentRegistry<W> {
+    /// Create a new empty [`ComponentRegistry`].
+    pub fn new() -> Self {
+        Self {
+            infos: Vec::new(),
+            by_type_id: HashMap::new(),
+            by_name: HashMap::new(),
+        }
+    }
+
+    /// Register component `C` with a stable `name`, replacing its previous registration (if any).
+    ///
+    /// Hooks are added via the returned [`RegisterComponent`].
+    ///
+    /// Panics if the name is already used by another component.
+    pub fn register<C: 'static>(&mut self, name: &'static str) -> RegisterComponent<'_, W, C>
+    where
+        ThreadSafe<W::Erased, C>: Send + Sync,
+    {
+        let type_id = TypeId::of::<C>();
+        let info = ComponentInfo {
+            name,
+            type_id,
+            type_name: type_name::<C>(),
+            size: size_of::<C>(),
+            insert: |world: &mut W, entity, component| {
+                let component = *component
+                    .downcast::<C>()
+                    .expect("Component not of the registered type");
+                if !world.world().entities.0.has(entity) {
+                    return Err(Box::new(component));
+                }
+                W::AssocEntityBuilder::create(entity, world.world_mut()).insert(component);
+
+                Ok(())
+            },
+            debug: None,
+            clone: None,
+            default: None,
+            #[cfg(feature = "serde")]
+            serialize: None,
+            #[cfg(feature = "serde")]
+            deserialize: None,
+        };
+
+        if let Some(index) = self.by_name.get(name) {
+            assert!(
+                self.infos[*index].type_id == type_id,
+                "Component name `{name}` already registered"
+            );
+        }
+
+        // Re-registering replaces the info...
+        let index = match self.by_type_id.get(&type_id) {
+            Some(&index) => {
+                self.by_name.remove(self.infos[index].name);
+                self.infos[index] = info;
+                index
+            }
+            None => {
+                self.infos.push(info);
+                self.by_type_id.insert(type_id, self.infos.len() - 1);
+                self.infos.len() - 1
+            }
+        };
+        self.by_name.insert(name, index);
+
+        RegisterComponent {
+            info: &mut self.infos[index],
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Get the info of component `C`.
+    pub fn get<C: 'static>(&self) -> Option<&ComponentInfo<W>> {
+        self.get_by_type_id(TypeId::of::<C>())
+    }
+
+    /// Get the info of component with the given `TypeId`.
+    pub fn get_by_type_id(&self, type_id: TypeId) -> Option<&ComponentInfo<W>> {
+        self.by_type_id
+            .get(&type_id)
+            .map(|index| &self.infos[*index])
+    }
+
+    /// Get the info of component with the given name.
+    pub fn get_by_name(&self, name: &str) -> Option<&ComponentInfo<W>> {
+        self.by_name.get(name).map(|index| &self.infos[*index])
+    }
+
+    /// Iterate over the infos of all registered components, in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &ComponentInfo<W>> {
+        self.infos.iter()
+    }
+
+    /// Infos of the registered components of the entity, in registration order.
+    ///
+    /// Empty if the entity does not exist.
+    pub fn components(&self, world: &W, entity: W::Key) -> Vec<&ComponentInfo<W>> {
+        world
+            .world()
+            .entities
+            .0
+            .get(entity)
+            .map(|entity| {
+                self.entity_components(world, entity)
+                    .into_iter()
+                    .map(|(info, _)| info)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Registered components of the entity with their infos, in registration order.
+    pub(crate) fn entity_components<'w>(
+        &self,
+        world: &'w W,
+        entity: &'w TypeMap<W::Erased>,
+    ) -> Vec<(&ComponentInfo<W>, &'w dyn Any)> {
+        let all_tables = &world.world().all_tables;
+        let mut components = entity
+            .iter()
+            .filter_map(|(type_id, component_id)| {
+                let table = all_tables.get_erased(type_id)?;
+                let index = *self.by_type_id.get(&table.component_type_id())?;
+                let component = table.get_by_component_id(component_id)?;
+                Some((index, component))
+            })
+            .collect::<Vec<_>>();
+        // Registration order, for a stable output...
+        components.sort_by_key(|(index, _)| *index);
+
+        components
+            .into_iter()
+            .map(|(index, component)| (&self.infos[index], component))
+            .collect()
+    }
+}
+
+impl<W: WorldMethods> Default for ComponentRegistry<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: WorldMethods> Debug for ComponentRegistry<W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entries(self.infos.iter().map(|info| info.name))
+            .finish()
+    }
+}
+
+/// Adds hooks to a component registered via [`ComponentRegistry::register`].
+pub struct RegisterComponent<'a, W: WorldMethods, C> {
+    info: &'a mut ComponentInfo<W>,
+    _phantom_data: PhantomData<fn() -> C>,
+}
+
+impl<W: WorldMethods, C: 'static> RegisterComponent<'_, W, C>
+where
+    ThreadSafe<W::Erased, C>: Send + Sync,
+{
+    /// Add a hook formatting the component via `Debug`.
+    pub fn with_debug(&mut self) -> &mut Self
+    where
+        C: Debug,
+    {
+        self.info.debug =
+            Some(|world, entity| Some(format!("{:?}", world.component::<C>(entity)?)));
+
+        self
+    }
+
+    /// Add a hook cloning the component between entities via `Clone`.
+    pub fn with_clone(&mut self) -> &mut Self
+    where
+        C: Clone,
+    {
+        self.info.clone = Some(|world, source, target| {
+            let Some(component) = world.component::<C>(source).cloned() else {
+                return false;
+            };
+            if !world.world().entities.0.has(target) {
+                return false;
+            }
+            W::AssocEntityBuilder::create(target, world.world_mut()).insert(component);
+
+            true
+        });
+
+        self
+    }
+
+    /// Add a hook inserting the default component via `Default`.
+    pub fn with_default(&mut self) -> &mut Self
+    where
+        C: Default,
+    {
+        self.info.default = Some(|world, entity| {
+            if !world.world().entities.0.has(entity) {
+                return false;
+            }
+            W::AssocEntityBuilder::create(entity, world.world_mut()).insert(C::default());
+
+            true
+        });
+
+        self
+    }
+
+    /// Add hooks (de)serializing the component via serde.
+    #[cfg(feature = "serde")]
+    pub fn with_serde(&mut self) -> &mut Self
+    where
+        C: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        self.info.serialize = Some(|component| component.downcast_ref::<C>().unwrap());
+        self.info.deserialize = Some(|deserializer| {
+            let component: Box<dyn Any> = Box::new(erased_serde::deserialize::<C>(deserializer)?);
+            Ok(component)
+        });
+
+        self
+    }
+}
+
+#[cfg(all(test, feature = "slab"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn component_registry_hooks() {
+        #[derive(Debug, Clone, Default, PartialEq)]
+        struct Health(u32);
+        #[derive(Debug, PartialEq)]
+        struct Name(&'static str);
+        struct Marker;
+
+        let mut world = World::new();
+        let a = world
+            .spawn()
+            .insert(Marker)
+            .insert(Health(5))
+            .insert(Name("a"))
+            .id;
+        let b = world.spawn().id;
+
+        let mut registry = ComponentRegistry::new();
+        registry
+            .register::<Health>("Health")
+            .with_debug()
+            .with_clone()
+            .with_default();
+        registry.register::<Name>("Name").with_debug();
+
+        let infos = registry
+            .iter()
+            .map(|info| (info.name(), info.size()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            infos,
+            vec![("Health", size_of::<Health>()), ("Name", size_of::<Name>())]
+        );
+        // Registration order, unregistered components skipped...
+        let names = registry
+            .components(&world, a)
+            .iter()
+            .map(|info| info.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Health", "Name"]);
+        assert!(registry.components(&world, b).is_empty());
+
+        let health = registry.get::<Health>().unwrap();
+        let name = registry.get_by_name("Name").unwrap();
+        assert_eq!(name.type_name(), core::any::type_name::<Name>());
+        assert_eq!(health.debug(&world, a).as_deref(), Some("Health(5)"));
+        assert_eq!(name.debug(&world, a).as_deref(), Some("Name(\"a\")"));
+        assert_eq!(health.debug(&world, b), None);
+
+        // Hooks not added...
+        assert!(!name.clone_component(&mut world, a, b));
+        assert!(!name.insert_default(&mut world, b));
+
+        assert!(health.clone_component(&mut world, a, b));
+        assert_eq!(world.component::<Health>(b), Some(&Health(5)));
+        world.component_mut::<Health>(b).unwrap().0 = 7;
+        assert_eq!(world.component::<Health>(a), Some(&Health(5)));
+
+        assert!(health.insert_default(&mut world, b));
+        assert_eq!(world.component::<Health>(b), Some(&Health(0)));
+        assert!(
+            health
+                .insert_boxed(&mut world, b, Box::new(Health(3)))
+                .is_ok()
+        );
+        assert_eq!(world.component::<Health>(b), Some(&Health(3)));
+
+        world.despawn(b);
+        assert!(!health.clone_component(&mut world, a, b));
+        assert!(!health.insert_default(&mut world, b));
+        let component = health
+            .insert_boxed(&mut world, b, Box::new(Health(1)))
+            .unwrap_err();
+        assert_eq!(component.downcast_ref::<Health>(), Some(&Health(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Component not of the registered type")]
+    fn component_registry_insert_boxed_wrong_type_panics() {
+        struct Health(#[allow(dead_code)] u32);
+
+        let mut world = World::new();
+        let a = world.spawn().id;
+        let mut registry = ComponentRegistry::new();
+        registry.register::<Health>("Health");
+        let _ = registry
+            .get::<Health>()
+            .unwrap()
+            .insert_boxed(&mut world, a, Box::new(1_u32));
+    }
+}
@@ -0,0 +1,241 @@
+use core::fmt::Debug;
+
+use crate::{
+    commands::Commands,
+    system::{IntoSystem, System},
+    world::WorldMethods,
+};
+
+/// Stages of a [`Schedule`], run in the declared order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// Run once, on the first [`Schedule::run`].
+    Startup,
+    /// Run on every [`Schedule::run`].
+    Update,
+    /// Run on every [`Schedule::run`], after [`Stage::Update`].
+    PostUpdate,
+}
+
+/// Strategy running the systems of a stage of a [`Schedule`], eg. [`SequentialExecutor`].
+pub trait Executor<W: WorldMethods> {
+    /// Boxed systems run by the executor, eg. `dyn System<W> + Send` to run them on other
+    /// threads.
+    type System: ?Sized + System<W>;
+
+    /// Run the systems of a stage against the world, with the same results as running them in
+    /// the order they were added, and apply the [`Commands`] they recorded.
+    fn run_stage(&mut self, systems: &mut [Box<Self::System>], world: &mut W);
+}
+
+/// Conversion of systems into `B`, the boxed systems run by an [`Executor`].
+pub trait IntoBoxedSystem<B: ?Sized> {
+    /// Box the system.
+    fn into_boxed(self) -> Box<B>;
+}
+
+impl<W: WorldMethods, S: System<W> + 'static> IntoBoxedSystem<dyn System<W>> for S {
+    fn into_boxed(self) -> Box<dyn System<W>> {
+        Box::new(self)
+    }
+}
+
+/// [`Executor`] running systems one after another, the default of a [`Schedule`].
+pub struct SequentialExecutor<W: WorldMethods> {
+    /// Commands recorded by the systems of the running stage.
+    commands: Commands<W>,
+}
+
+impl<W: WorldMethods + 'static> SequentialExecutor<W> {
+    /// Create a new [`SequentialExecutor`].
+    pub fn new() -> Self {
+        Self {
+            commands: Commands::new(),
+        }
+    }
+}
+
+impl<W: WorldMethods + 'static> Executor<W> for SequentialExecutor<W> {
+    type System = dyn System<W>;
+
+    fn run_stage(&mut self, systems: &mut [Box<dyn System<W>>], world: &mut W) {
+        systems
+            .iter_mut()
+            .for_each(|system| system.run(world, &mut self.commands));
+        self.commands.apply(world);
+    }
+}
+
+impl<W: WorldMethods + 'static> Default for SequentialExecutor<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: WorldMethods> Debug for SequentialExecutor<W> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SequentialExecutor").finish_non_exhaustive()
+    }
+}
+
+/// Systems registered by [`Stage`], run against a world via the [`Executor`] `X`.
+///
+/// Systems of a stage run in the order they were added (or with the same results, eg. via a
+/// `ParallelSchedule` with the `rayon` feature). [`Commands`] recorded by systems are applied at
+/// the end of each stage.
+///
+/// # Examples
+///
+/// ```
+/// # use anvaya::prelude::*;
+/// # use slab::Slab;
+/// struct Position(f32);
+/// struct Velocity(f32);
+/// struct Frames(u32);
+///
+/// fn setup(world: &mut World<usize, Slab<TypeMap>>) {
+///     world.spawn().insert(Position(0.0)).insert(Velocity(1.0));
+///     world.insert_resource(Frames(0));
+/// }
+///
+/// fn movement(query: Query<usize, (&mut Position, &Velocity)>) {
+///     for (_, (position, velocity)) in query {
+///         position.0 += velocity.0;
+///     }
+/// }
+///
+/// fn count_frames(mut frames: ResMut<Frames>) {
+///     frames.0 += 1;
+/// }
+///
+/// let mut world = World::new();
+/// let mut schedule = Schedule::new();
+/// schedule
+///     .add_system(Stage::Startup, setup)
+///     .add_system(Stage::Update, movement)
+///     .add_system(Stage::PostUpdate, count_frames);
+///
+/// schedule.run(&mut world);
+/// schedule.run(&mut world);
+/// assert_eq!(world.resource::<Frames>().unwrap().0, 2);
+/// let mut query = world.query();
+/// let (_, position) = query.get::<&Position>().unwrap().next().unwrap();
+/// assert_eq!(position.0, 2.0);
+/// ```
+pub struct Schedule<W: WorldMethods, X: Executor<W> = SequentialExecutor<W>> {
+    startup: Vec<Box<X::System>>,
+    update: Vec<Box<X::System>>,
+    post_update: Vec<Box<X::System>>,
+    executor: X,
+    startup_done: bool,
+}
+
+impl<W: WorldMethods + 'static> Schedule<W> {
+    /// Create a new empty [`Schedule`], running systems one after another.
+    pub fn new() -> Self {
+        Self::with_executor(SequentialExecutor::new())
+    }
+}
+
+impl<W: WorldMethods, X: Executor<W>> Schedule<W, X> {
+    /// Create a new empty [`Schedule`], running the systems of its stages via the executor.
+    pub fn with_executor(executor: X) -> Self {
+        Self {
+            startup: Vec::new(),
+            update: Vec::new(),
+            post_update: Vec::new(),
+            executor,
+            startup_done: false,
+        }
+    }
+
+    /// Add a system (usually a plain function, see [`IntoSystem`]) to the stage.
+    pub fn add_system<M>(
+        &mut self,
+        stage: Stage,
+        system: impl IntoSystem<W, M, System: IntoBoxedSystem<X::System>>,
+    ) -> &mut Self {
+        self.systems_mut(stage)
+            .push(system.into_system().into_boxed());
+
+        self
+    }
+
+    /// Run the stages in order against the world.
+    ///
+    /// [`Stage::Startup`] systems only run on the first call.
+    pub fn run(&mut self, world: &mut W) {
+        if !self.startup_done {
+            self.executor.run_stage(&mut self.startup, world);
+            self.startup_done = true;
+        }
+        self.executor.run_stage(&mut self.update, world);
+        self.executor.run_stage(&mut self.post_update, world);
+    }
+
+    pub(crate) fn systems_mut(&mut self, stage: Stage) -> &mut Vec<Box<X::System>> {
+        match stage {
+            Stage::Startup => &mut self.startup,
+            Stage::Update => &mut self.update,
+            Stage::PostUpdate => &mut self.post_update,
+        }
+    }
+}
+
+impl<W: WorldMethods, X: Executor<W> + Default> Default for Schedule<W, X> {
+    fn default() -> Self {
+        Self::with_executor(X::default())
+    }
+}
+
+impl<W: WorldMethods, X: Executor<W> + Debug> Debug for Schedule<W, X> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Schedule")
+            .field("startup", &self.startup.len())
+            .field("update", &self.update.len())
+            .field("post_update", &self.post_update.len())
+            .field("executor", &self.executor)
+            .field("startup_done", &self.startup_done)
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "slab"))]
+mod tests {
+    use slab::Slab;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn schedule_runs_stages_in_order() {
+        struct Log(Vec<&'static str>);
+
+        let mut world = World::new();
+        world.insert_resource(Log(Vec::new()));
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(Stage::PostUpdate, |mut log: ResMut<Log>| {
+                log.0.push("post_update")
+            })
+            .add_system(Stage::Update, |mut log: ResMut<Log>| log.0.push("update"))
+            .add_system(Stage::Startup, |world: &mut World<usize, Slab<TypeMap>>| {
+                world.resource_mut::<Log>().unwrap().0.push("startup")
+            })
+            .add_system(Stage::Update, |mut log: ResMut<Log>| log.0.push("update_2"));
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+        assert_eq!(
+            world.resource::<Log>().unwrap().0,
+            vec![
+                "startup",
+                "update",
+                "update_2",
+                "post_update",
+                "update",
+                "update_2",
+                "post_update"
+            ]
+        );
+    }
+}
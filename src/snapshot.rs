@@ -0,0 +1,414 @@
+use core::{any::Any, fmt::Formatter};
+use std::collections::HashMap;
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{DeserializeSeed, Error as DeError, MapAccess, SeqAccess, Visitor},
+    ser::{Error as SerError, SerializeMap, SerializeSeq},
+};
+
+use crate::{
+    entity_builder::EntityBuilderMethods,
+    registry::{ComponentRegistry, DeserializeFn},
+    storage::Storage,
+    type_map::TypeMap,
+    world::WorldMethods,
+};
+
+impl<W: WorldMethods> ComponentRegistry<W> {
+    /// Get a serializable snapshot of the world.
+    ///
+    /// Serialized as a sequence of entities, each a `[entity key, components]` pair with the
+    /// components as a map of component name to value. Components not registered with
+    /// [`RegisterComponent::with_serde`] are skipped (see [`SerializableWorld::skipped`]), or
+    /// fail the serialization with [`SerializableWorld::strict`].
+    ///
+    /// [`RegisterComponent::with_serde`]: crate::prelude::RegisterComponent::with_serde
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anvaya::prelude::*;
+    /// # use serde::{Deserialize, Serialize};
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// struct Player(String);
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// struct Age(u8);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn().insert(Player("Mike".to_string())).insert(Age(30));
+    ///
+    /// let mut registry = ComponentRegistry::new();
+    /// registry.register::<Player>("Player").with_serde();
+    /// registry.register::<Age>("Age").with_serde();
+    ///
+    /// let json = serde_json::to_string(&registry.serializable(&world)).unwrap();
+    /// assert_eq!(json, r#"[[0,{"Player":"Mike","Age":30}]]"#);
+    ///
+    /// let world = registry
+    ///     .deserialize(&mut serde_json::Deserializer::from_str(&json))
+    ///     .unwrap()
+    ///     .world;
+    /// let mut query = world.query();
+    /// let (_, (player, age)) = query.get::<(&Player, &Age)>().unwrap().next().unwrap();
+    /// assert_eq!((player.0.as_str(), age.0), ("Mike", 30));
+    /// ```
+    pub fn serializable<'a>(&'a self, world: &'a W) -> SerializableWorld<'a, W> {
+        SerializableWorld {
+            registry: self,
+            world,
+            strict: false,
+        }
+    }
+
+    /// Deserialize a new world from the format of [`ComponentRegistry::serializable`].
+    ///
+    /// Entities are spawned anew, hence may get other keys than in the snapshot, see
+    /// [`DeserializedWorld::entities`]. Errors on components not registered with serde hooks,
+    /// and on entity keys repeated in the snapshot.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        &self,
+        deserializer: D,
+    ) -> Result<DeserializedWorld<W>, D::Error>
+    where
+        W::Key: Deserialize<'de>,
+    {
+        deserializer.deserialize_seq(WorldVisitor { registry: self })
+    }
+}
+
+/// World deserialized by [`ComponentRegistry::deserialize`].
+#[derive(Debug)]
+pub struct DeserializedWorld<W: WorldMethods> {
+    /// The new world.
+    pub world: W,
+    /// Keys of the entities in the snapshot mapped to the keys of the entities spawned for them,
+    /// eg. to remap entity keys stored in components.
+    pub entities: HashMap<W::Key, W::Key>,
+}
+
+/// Serializable snapshot of a world, see [`ComponentRegistry::serializable`].
+pub struct SerializableWorld<'a, W: WorldMethods> {
+    registry: &'a ComponentRegistry<W>,
+    world: &'a W,
+    /// Whether skipped components fail the serialization.
+    strict: bool,
+}
+
+impl<W: WorldMethods> SerializableWorld<'_, W> {
+    /// Fail the serialization on components not registered with serde hooks, instead of
+    /// skipping them.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Names of the components skipped when serializing, ie. not registered with serde hooks.
+    ///
+    /// Registered components are named by their registered name, others by their type name.
+    /// Each name is listed once, in the order first found.
+    pub fn skipped(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        for (_, entity) in self.world.world().entities.0.iter() {
+            for name in self.skipped_components(entity) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+
+        names
+    }
+
+    /// Names of the components of the entity not registered with serde hooks.
+    fn skipped_components(&self, entity: &TypeMap<W::Erased>) -> Vec<&'static str> {
+        let all_tables = &self.world.world().all_tables;
+        entity
+            .iter()
+            .filter_map(|(type_id, _)| {
+                let table = all_tables.get_erased(type_id)?;
+                match self.registry.get_by_type_id(table.component_type_id()) {
+                    Some(info) if info.serialize.is_some() => None,
+                    Some(info) => Some(info.name()),
+                    None => Some(table.component_type_name()),
+                }
+            })
+            .collect()
+    }
+}
+
+impl<W: WorldMethods<Key: Serialize>> Serialize for SerializableWorld<'_, W> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let world = self.world.world();
+        let mut seq = serializer.serialize_seq(Some(world.entities.0.len()))?;
+        for (key, entity) in world.entities.0.iter() {
+            if self.strict {
+                let skipped = self.skipped_components(entity);
+                if !skipped.is_empty() {
+                    return Err(S::Error::custom(format!(
+                        "Components of entity {key} not registered for serde: `{}`",
+                        skipped.join("`, `")
+                    )));
+                }
+            }
+            seq.serialize_element(&(
+                key,
+                SerializableEntity {
+                    registry: self.registry,
+                    world: self.world,
+                    entity,
+                },
+            ))?;
+        }
+
+        seq.end()
+    }
+}
+
+/// Serializable components of an entity.
+struct SerializableEntity<'a, W: WorldMethods> {
+    registry: &'a ComponentRegistry<W>,
+    world: &'a W,
+    entity: &'a TypeMap<W::Erased>,
+}
+
+impl<W: WorldMethods> Serialize for SerializableEntity<'_, W> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let components = self
+            .registry
+            .entity_components(self.world, self.entity)
+            .into_iter()
+            .filter_map(|(info, component)| Some((info.name(), info.serialize?(component))))
+            .collect::<Vec<_>>();
+
+        let mut map = serializer.serialize_map(Some(components.len()))?;
+        for (name, component) in components {
+            map.serialize_entry(name, component)?;
+        }
+
+        map.end()
+    }
+}
+
+/// Deserializes a new world.
+struct WorldVisitor<'a, W: WorldMethods> {
+    registry: &'a ComponentRegistry<W>,
+}
+
+impl<'de, W: WorldMethods<Key: Deserialize<'de>>> Visitor<'de> for WorldVisitor<'_, W> {
+    type Value = DeserializedWorld<W>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("a sequence of entities")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut world = W::new();
+        let mut entities = HashMap::new();
+        while let Some((key, entity)) = seq.next_element_seed(EntitySeed {
+            registry: self.registry,
+            world: &mut world,
+        })? {
+            if entities.insert(key, entity).is_some() {
+                return Err(A::Error::custom(format!(
+                    "Duplicate entity {key} in snapshot"
+                )));
+            }
+        }
+
+        Ok(DeserializedWorld { world, entities })
+    }
+}
+
+/// Spawns an entity for a `[entity key, components]` pair, returning the key in the snapshot
+/// and the key of the spawned entity.
+struct EntitySeed<'a, W: WorldMethods> {
+    registry: &'a ComponentRegistry<W>,
+    world: &'a mut W,
+}
+
+impl<'de, W: WorldMethods<Key: Deserialize<'de>>> DeserializeSeed<'de> for EntitySeed<'_, W> {
+    type Value = (W::Key, W::Key);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_tuple(2, self)
+    }
+}
+
+impl<'de, W: WorldMethods<Key: Deserialize<'de>>> Visitor<'de> for EntitySeed<'_, W> {
+    type Value = (W::Key, W::Key);
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("an entity key and a map of component names to values")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let key = seq
+            .next_element::<W::Key>()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let entity = self.world.spawn().id();
+        seq.next_element_seed(ComponentsSeed {
+            registry: self.registry,
+            world: self.world,
+            entity,
+        })?
+        .ok_or_else(|| A::Error::invalid_length(1, &"an entity key and components"))?;
+
+        Ok((key, entity))
+    }
+}
+
+/// Inserts the deserialized components into the entity.
+struct ComponentsSeed<'a, W: WorldMethods> {
+    registry: &'a ComponentRegistry<W>,
+    world: &'a mut W,
+    entity: W::Key,
+}
+
+impl<'de, W: WorldMethods> DeserializeSeed<'de> for ComponentsSeed<'_, W> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, W: WorldMethods> Visitor<'de> for ComponentsSeed<'_, W> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("a map of component names to values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let entity = self.entity;
+        while let Some(name) = map.next_key::<String>()? {
+            let (info, deserialize) = self
+                .registry
+                .get_by_name(&name)
+                .and_then(|info| Some((info, info.deserialize?)))
+                .ok_or_else(|| {
+                    A::Error::custom(format!("Component `{name}` not registered for serde"))
+                })?;
+            let component = map.next_value_seed(ComponentSeed { deserialize })?;
+            info.insert_boxed(self.world, entity, component)
+                .map_err(|_| A::Error::custom(format!("Entity {entity} not found")))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Deserializes a component using its registered hook.
+struct ComponentSeed {
+    deserialize: DeserializeFn,
+}
+
+impl<'de> DeserializeSeed<'de> for ComponentSeed {
+    type Value = Box<dyn Any>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.deserialize)(&mut deserializer).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "slab"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn serde_world_snapshot() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Player {
+            name: String,
+        }
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Position(f32, f32);
+        /// Runtime only, registered without serde hooks.
+        struct Handle;
+
+        /// Runtime only, not registered.
+        struct Cache;
+
+        let mut world = World::new();
+        world
+            .spawn()
+            .insert(Handle)
+            .insert(Position(1.0, 2.0))
+            .insert(Player {
+                name: "Mike".to_string(),
+            });
+        let despawned = world.spawn().id;
+        world.spawn().insert(Position(0.5, 0.0)).insert(Cache);
+        world.despawn(despawned);
+
+        let mut registry = ComponentRegistry::new();
+        registry.register::<Player>("Player").with_serde();
+        registry.register::<Position>("Position").with_serde();
+        registry.register::<Handle>("Handle");
+
+        let snapshot = registry.serializable(&world);
+        assert_eq!(
+            snapshot.skipped(),
+            vec!["Handle", core::any::type_name::<Cache>()]
+        );
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(
+            json,
+            r#"[[0,{"Player":{"name":"Mike"},"Position":[1.0,2.0]}],[2,{"Position":[0.5,0.0]}]]"#
+        );
+        // Strict snapshots fail on skipped components...
+        let error = serde_json::to_string(&registry.serializable(&world).strict()).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Components of entity 0 not registered for serde: `Handle`")
+        );
+
+        // Entities are spawned anew, with their keys in the snapshot mapped to the new ones...
+        let deserialized = registry
+            .deserialize(&mut serde_json::Deserializer::from_str(&json))
+            .unwrap();
+        assert_eq!(
+            deserialized.entities,
+            [(0, 0), (2, 1)].into_iter().collect()
+        );
+        let world = deserialized.world;
+        let mut query = world.query();
+        let results = query
+            .get::<(&Position, Option<&Player>)>()
+            .unwrap()
+            .map(|(_, (position, player))| (position.0, player.map(|p| p.name.as_str())))
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![(1.0, Some("Mike")), (0.5, None)]);
+        assert_eq!(world.query().get::<&Handle>().map(|r| r.count()), None);
+        // Round trips (with the remapped keys)...
+        assert_eq!(
+            serde_json::to_string(&registry.serializable(&world).strict()).unwrap(),
+            json.replace("[2,", "[1,")
+        );
+
+        // Unknown components are an error...
+        let error = registry
+            .deserialize(&mut serde_json::Deserializer::from_str(
+                r#"[[0,{"Handle":null}]]"#,
+            ))
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Component `Handle` not registered for serde")
+        );
+
+        // Repeated entity keys are an error...
+        let error = registry
+            .deserialize(&mut serde_json::Deserializer::from_str(
+                r#"[[0,{"Position":[0.0,0.0]}],[0,{"Position":[1.0,0.0]}]]"#,
+            ))
+            .unwrap_err();
+        assert!(error.to_string().contains("Duplicate entity 0 in snapshot"));
+    }
+}
@@ -18,6 +18,11 @@ pub trait Storage: Default {
     /// Should be able to expand on demand.
     fn with_capacity(capacity: usize) -> Self;
 
+    /// Reserve capacity for at least `additional` more values.
+    ///
+    /// Does nothing by default, ie. the storage only expands on demand.
+    fn reserve(&mut self, _additional: usize) {}
+
     /// Insert a [`Storage::Value`] and return a lookup [`Storage::Key`].
     fn insert(&mut self, val: Self::Value) -> Self::Key;
 
@@ -27,6 +32,11 @@ pub trait Storage: Default {
     /// Get [`Storage::Value`] mutable ref using given [`Storage::Key`].
     fn get_mut(&mut self, key: Self::Key) -> Option<&mut Self::Value>;
 
+    /// Remove [`Storage::Value`] for given [`Storage::Key`] and return it.
+    ///
+    /// Returns `None` if there was no value for the key.
+    fn remove(&mut self, key: Self::Key) -> Option<Self::Value>;
+
     /// Get [`Storage::Key`] of given [`Storage::Value`] ref.
     fn key_of(&self, val: &Self::Value) -> Self::Key;
 
@@ -41,6 +51,9 @@ pub trait Storage: Default {
 
     /// Iterate over `(key, &value)` pairs of [`Storage`].
     fn iter(&self) -> impl Iterator<Item = (Self::Key, &Self::Value)>;
+
+    /// Iterate over `(key, &mut value)` pairs of [`Storage`].
+    fn iter_mut(&mut self) -> impl Iterator<Item = (Self::Key, &mut Self::Value)>;
 }
 
 /// Trait for identifier keys of [`Storage`].
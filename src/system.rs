@@ -0,0 +1,718 @@
+use core::{
+    any::{TypeId, type_name},
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "rayon")]
+use crate::query_data::split_accesses;
+use crate::{
+    commands::Commands,
+    query_data::{Access, QueryDataMut, TablesMut},
+    storage::{Identifier, Storage},
+    type_map::{AnyValue, TypeMap},
+    world::{ComponentId, ComponentTicks, WorldMethods, WorldValue},
+};
+
+/// Logic run against a world, usually registered on a [`Schedule`](crate::prelude::Schedule).
+pub trait System<W: WorldMethods> {
+    /// Run the system, recording deferred structural changes into `commands`.
+    fn run(&mut self, world: &mut W, commands: &mut Commands<W>);
+
+    /// Get the system as a [`ParallelSystem`], able to run in parallel with other systems.
+    ///
+    /// `None` by default, ie. the system runs alone with exclusive access to the world.
+    fn as_parallel(&mut self) -> Option<&mut dyn ParallelSystem<W>> {
+        None
+    }
+}
+
+/// [`System`] declaring its access to the world, so it can run in parallel with systems not
+/// conflicting with it, see [`System::as_parallel`].
+///
+/// Implemented by systems made from functions taking [`SystemParam`]s.
+pub trait ParallelSystem<W: WorldMethods> {
+    /// Components and resources accessed by the system.
+    fn access(&self) -> SystemAccess;
+
+    /// Run the system on the part of the world split off for its [`ParallelSystem::access`].
+    fn run_with(&mut self, world: SystemWorld<'_, W>);
+}
+
+/// Components and resources read and written by a [`System`], used to run systems which do
+/// not conflict in parallel.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SystemAccess {
+    /// `TypeId`s of the [`ComponentId`]s (ie. table keys) of components read.
+    component_reads: HashSet<TypeId>,
+    /// `TypeId`s of the [`ComponentId`]s (ie. table keys) of components written.
+    component_writes: HashSet<TypeId>,
+    resource_reads: HashSet<TypeId>,
+    resource_writes: HashSet<TypeId>,
+    /// Whether the whole world is accessed mutably.
+    exclusive: bool,
+}
+
+impl SystemAccess {
+    /// Create a new [`SystemAccess`] accessing nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark the whole world as accessed mutably, conflicting with any other access.
+    pub fn set_exclusive(&mut self) -> &mut Self {
+        self.exclusive = true;
+        self
+    }
+
+    /// If the whole world is accessed mutably.
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    /// Mark component `C` as read.
+    pub fn read_component<C: 'static, I: Identifier + 'static>(&mut self) -> &mut Self {
+        self.component_reads
+            .insert(TypeId::of::<ComponentId<C, I>>());
+        self
+    }
+
+    /// Mark component `C` as written.
+    pub fn write_component<C: 'static, I: Identifier + 'static>(&mut self) -> &mut Self {
+        self.component_writes
+            .insert(TypeId::of::<ComponentId<C, I>>());
+        self
+    }
+
+    /// Mark resource `R` as read.
+    pub fn read_resource<R: 'static>(&mut self) -> &mut Self {
+        self.resource_reads.insert(TypeId::of::<R>());
+        self
+    }
+
+    /// Mark resource `R` as written.
+    pub fn write_resource<R: 'static>(&mut self) -> &mut Self {
+        self.resource_writes.insert(TypeId::of::<R>());
+        self
+    }
+
+    /// Whether both accesses can not run in parallel, ie. either is exclusive, or one writes
+    /// something the other reads or writes.
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        let conflicts = |reads: &HashSet<TypeId>,
+                         writes: &HashSet<TypeId>,
+                         other_reads: &HashSet<TypeId>,
+                         other_writes: &HashSet<TypeId>| {
+            !writes.is_disjoint(other_reads)
+                || !writes.is_disjoint(other_writes)
+                || !reads.is_disjoint(other_writes)
+        };
+
+        self.exclusive
+            || other.exclusive
+            || conflicts(
+                &self.component_reads,
+                &self.component_writes,
+                &other.component_reads,
+                &other.component_writes,
+            )
+            || conflicts(
+                &self.resource_reads,
+                &self.resource_writes,
+                &other.resource_reads,
+                &other.resource_writes,
+            )
+    }
+
+    #[cfg(feature = "rayon")]
+    pub(crate) fn component_reads(&self) -> &HashSet<TypeId> {
+        &self.component_reads
+    }
+
+    #[cfg(feature = "rayon")]
+    pub(crate) fn component_writes(&self) -> &HashSet<TypeId> {
+        &self.component_writes
+    }
+}
+
+/// Conversion of plain functions into [`System`]s.
+///
+/// Implemented for functions taking `&mut W` (exclusive access to the world), or taking any
+/// number (up to 8) of [`SystemParam`]s like [`Query`], [`Res`], [`ResMut`] and `&mut Commands`.
+///
+/// `Marker` only tells the implementations apart, it never needs to be specified.
+pub trait IntoSystem<W: WorldMethods, Marker> {
+    type System: System<W> + 'static;
+
+    fn into_system(self) -> Self::System;
+}
+
+impl<W: WorldMethods, M: 'static, F: 'static> IntoSystem<W, M> for F
+where
+    FunctionSystem<F, M>: System<W>,
+{
+    type System = FunctionSystem<F, M>;
+
+    fn into_system(self) -> Self::System {
+        FunctionSystem {
+            function: self,
+            last_run: 0,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// [`System`] made from a plain function, see [`IntoSystem`].
+pub struct FunctionSystem<F, M> {
+    function: F,
+    /// Change tick of the last run, for change detection.
+    last_run: u32,
+    _marker: PhantomData<fn() -> M>,
+}
+
+/// Marker for systems having exclusive access to the world.
+pub struct Exclusive;
+
+impl<W: WorldMethods, F: FnMut(&mut W)> System<W> for FunctionSystem<F, Exclusive> {
+    fn run(&mut self, world: &mut W, _commands: &mut Commands<W>) {
+        self.last_run = world.world_mut().ticks.increment();
+        (self.function)(world)
+    }
+}
+
+macro_rules! impl_system_function {
+    ($($param:ident),*) => {
+        impl<W, F, $($param),*> System<W> for FunctionSystem<F, fn($($param,)*)>
+        where
+            W: WorldMethods + 'static,
+            $($param: SystemParam<W>,)*
+            F: FnMut($($param),*) + for<'w> FnMut($($param::Item<'w>),*),
+        {
+            fn run(&mut self, world: &mut W, commands: &mut Commands<W>) {
+                let change_tick = world.world_mut().ticks.increment();
+                self.run_with(SystemWorld::new(world, Some(commands), change_tick));
+            }
+
+            fn as_parallel(&mut self) -> Option<&mut dyn ParallelSystem<W>> {
+                Some(self)
+            }
+        }
+
+        impl<W, F, $($param),*> ParallelSystem<W> for FunctionSystem<F, fn($($param,)*)>
+        where
+            W: WorldMethods + 'static,
+            $($param: SystemParam<W>,)*
+            F: FnMut($($param),*) + for<'w> FnMut($($param::Item<'w>),*),
+        {
+            #[allow(unused_mut)]
+            fn access(&self) -> SystemAccess {
+                let mut access = SystemAccess::new();
+                $($param::access(&mut access);)*
+                access
+            }
+
+            #[allow(non_snake_case, unused_variables, unused_mut)]
+            fn run_with(&mut self, mut world: SystemWorld<'_, W>) {
+                world.last_run = self.last_run;
+                self.last_run = world.change_tick;
+                $(let $param = $param::fetch(&mut world);)*
+                (self.function)($($param),*);
+            }
+        }
+    };
+}
+
+impl_system_function!();
+impl_system_function!(P0);
+impl_system_function!(P0, P1);
+impl_system_function!(P0, P1, P2);
+impl_system_function!(P0, P1, P2, P3);
+impl_system_function!(P0, P1, P2, P3, P4);
+impl_system_function!(P0, P1, P2, P3, P4, P5);
+impl_system_function!(P0, P1, P2, P3, P4, P5, P6);
+impl_system_function!(P0, P1, P2, P3, P4, P5, P6, P7);
+
+/// Borrows of a world split by entities, component tables, resources and commands,
+/// from which [`SystemParam`]s are fetched.
+pub struct SystemWorld<'w, W: WorldMethods> {
+    entities: &'w W::EntityStorage,
+    tables: TablesMut<'w, W::Key, W::Erased>,
+    resources: HashMap<TypeId, Access<'w, W::Erased>>,
+    commands: Option<&'w mut Commands<W>>,
+    /// Tick of the running system, stamped on components accessed mutably.
+    change_tick: u32,
+    /// Tick of the previous run of the system, for change detection.
+    last_run: u32,
+}
+
+impl<'w, W: WorldMethods> SystemWorld<'w, W> {
+    /// Borrow the whole world for a system run at `change_tick`.
+    pub(crate) fn new(
+        world: &'w mut W,
+        commands: Option<&'w mut Commands<W>>,
+        change_tick: u32,
+    ) -> Self {
+        let world = world.world_mut();
+        Self {
+            entities: &world.entities.0,
+            tables: TablesMut::new(&mut world.all_tables, change_tick),
+            resources: world
+                .resources
+                .0
+                .iter_mut_erased()
+                .map(|(type_id, resource)| (type_id, Access::Unique(resource)))
+                .collect(),
+            commands,
+            change_tick,
+            last_run: 0,
+        }
+    }
+
+    /// Split off the component tables and resources in `access`, for a system run at
+    /// `change_tick`.
+    ///
+    /// Panics if any of them were already split off mutably.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn split(
+        &mut self,
+        access: &SystemAccess,
+        commands: &'w mut Commands<W>,
+        change_tick: u32,
+    ) -> Self {
+        Self {
+            entities: self.entities,
+            tables: self.tables.split(access, change_tick),
+            resources: split_accesses(
+                &mut self.resources,
+                &access.resource_reads,
+                &access.resource_writes,
+            ),
+            commands: Some(commands),
+            change_tick,
+            last_run: 0,
+        }
+    }
+
+    /// Get access to resource `R`.
+    ///
+    /// Panics if the resource does not exist.
+    fn resource_access<R: 'static>(&mut self) -> &mut Access<'w, W::Erased> {
+        self.resources
+            .get_mut(&TypeId::of::<R>())
+            .unwrap_or_else(|| panic!("Resource `{}` does not exist", type_name::<R>()))
+    }
+}
+
+/// Parameter of a system function, fetched from the world each time the system runs.
+pub trait SystemParam<W: WorldMethods + 'static> {
+    /// The parameter as borrowed from the world.
+    type Item<'w>;
+
+    /// Fetch the parameter from the world.
+    fn fetch<'w>(world: &mut SystemWorld<'w, W>) -> Self::Item<'w>;
+
+    /// Declare the components and resources accessed by the parameter, exclusive access to the
+    /// world by default.
+    fn access(access: &mut SystemAccess) {
+        access.set_exclusive();
+    }
+}
+
+/// Commands of the running schedule, applied at the end of the current stage.
+///
+/// __Note: Panics if accessed more than once in a system.__
+impl<W: WorldMethods + 'static> SystemParam<W> for &mut Commands<W> {
+    type Item<'w> = &'w mut Commands<W>;
+
+    fn fetch<'w>(world: &mut SystemWorld<'w, W>) -> Self::Item<'w> {
+        world
+            .commands
+            .take()
+            .expect("Commands accessed more than once in a system")
+    }
+
+    /// Each system records into its own buffer, accessing nothing from the world.
+    fn access(_access: &mut SystemAccess) {}
+}
+
+/// Shared access to resource `R` in a system.
+///
+/// __Note: Panics if the resource does not exist, or is also accessed via [`ResMut`].__
+#[derive(Debug)]
+pub struct Res<'w, R>(&'w R);
+
+impl<R> Deref for Res<'_, R> {
+    type Target = R;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<W: WorldMethods + 'static, R: 'static> SystemParam<W> for Res<'_, R> {
+    type Item<'w> = Res<'w, R>;
+
+    fn fetch<'w>(world: &mut SystemWorld<'w, W>) -> Self::Item<'w> {
+        let resource = world
+            .resource_access::<R>()
+            .take_ref()
+            .expect("Resource accessed mutably more than once in a system");
+        Res(resource.as_any().downcast_ref::<R>().unwrap())
+    }
+
+    fn access(access: &mut SystemAccess) {
+        access.read_resource::<R>();
+    }
+}
+
+/// Mutable access to resource `R` in a system.
+///
+/// __Note: Panics if the resource does not exist, or is accessed more than once.__
+#[derive(Debug)]
+pub struct ResMut<'w, R>(&'w mut R);
+
+impl<R> Deref for ResMut<'_, R> {
+    type Target = R;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<R> DerefMut for ResMut<'_, R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
+
+impl<W: WorldMethods + 'static, R: 'static> SystemParam<W> for ResMut<'_, R> {
+    type Item<'w> = ResMut<'w, R>;
+
+    fn fetch<'w>(world: &mut SystemWorld<'w, W>) -> Self::Item<'w> {
+        let resource = world
+            .resource_access::<R>()
+            .take_mut()
+            .expect("Resource accessed mutably more than once in a system");
+        ResMut(resource.as_any_mut().downcast_mut::<R>().unwrap())
+    }
+
+    fn access(access: &mut SystemAccess) {
+        access.write_resource::<R>();
+    }
+}
+
+/// Query in a system, iterating `(entity key, Q::Item)` of entities matching the filter `F`.
+///
+/// - `Q` is any [`QueryDataMut`], eg. `(&mut Position, &Velocity)`.
+/// - `F` is any [`QueryFilter`], eg. `(With<Player>, Without<Dead>)`.
+///
+/// __Note: Accessing the same component type mutably more than once in a system panics.__
+pub struct Query<'w, I: Identifier + 'static, Q: QueryDataMut, F: QueryFilter = ()> {
+    /// Items of the matching entities, fetched lazily while iterating.
+    items: Box<dyn Iterator<Item = (I, Q::Item<'w>)> + 'w>,
+    _filter: PhantomData<F>,
+}
+
+impl<'w, I: Identifier + 'static, Q: QueryDataMut, F: QueryFilter> Iterator for Query<'w, I, Q, F> {
+    type Item = (I, Q::Item<'w>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next()
+    }
+}
+
+impl<
+    I: Identifier + 'static,
+    W: WorldMethods<Key = I> + 'static,
+    Q: QueryDataMut + 'static,
+    F: QueryFilter,
+> SystemParam<W> for Query<'_, I, Q, F>
+{
+    type Item<'w> = Query<'w, I, Q, F>;
+
+    fn fetch<'w>(world: &mut SystemWorld<'w, W>) -> Self::Item<'w> {
+        let entities = world
+            .entities
+            .iter()
+            .filter(|(_, entity)| F::matches::<I, W::Erased>(entity, &world.tables, world.last_run))
+            .collect::<Vec<_>>();
+        let items: Box<dyn Iterator<Item = _>> = match Q::init_fetch(&mut world.tables) {
+            Some(mut fetch) => {
+                Box::new(entities.into_iter().filter_map(move |(entity_id, entity)| {
+                    Some((entity_id, Q::fetch(&mut fetch, entity)?))
+                }))
+            }
+            None => Box::new(core::iter::empty()),
+        };
+
+        Query {
+            items,
+            _filter: PhantomData,
+        }
+    }
+
+    fn access(access: &mut SystemAccess) {
+        Q::access::<I>(access);
+        F::access::<I>(access);
+    }
+}
+
+/// Filter of entities in a system [`Query`].
+///
+/// Implemented for [`With`], [`Without`], [`Added`], [`Changed`] and tuples of them
+/// (up to 8 elements) matching all.
+pub trait QueryFilter {
+    /// Whether the entity (using its component ids) matches the filter.
+    ///
+    /// `last_run` is the change tick of the previous run of the system.
+    fn matches<I: Identifier + 'static, V: ?Sized + WorldValue>(
+        entity: &TypeMap<V>,
+        tables: &TablesMut<'_, I, V>,
+        last_run: u32,
+    ) -> bool;
+
+    /// Declare the components read by the filter, none by default.
+    fn access<I: Identifier + 'static>(_access: &mut SystemAccess) {}
+}
+
+impl QueryFilter for () {
+    fn matches<I: Identifier + 'static, V: ?Sized + WorldValue>(
+        _entity: &TypeMap<V>,
+        _tables: &TablesMut<'_, I, V>,
+        _last_run: u32,
+    ) -> bool {
+        true
+    }
+}
+
+/// Filter entities to ones having component `C`.
+#[derive(Debug)]
+pub struct With<C>(PhantomData<C>);
+
+impl<C: 'static> QueryFilter for With<C> {
+    fn matches<I: Identifier + 'static, V: ?Sized + WorldValue>(
+        entity: &TypeMap<V>,
+        _tables: &TablesMut<'_, I, V>,
+        _last_run: u32,
+    ) -> bool {
+        entity.has::<ComponentId<C, I>>()
+    }
+}
+
+/// Filter out entities having component `C`.
+#[derive(Debug)]
+pub struct Without<C>(PhantomData<C>);
+
+impl<C: 'static> QueryFilter for Without<C> {
+    fn matches<I: Identifier + 'static, V: ?Sized + WorldValue>(
+        entity: &TypeMap<V>,
+        _tables: &TablesMut<'_, I, V>,
+        _last_run: u32,
+    ) -> bool {
+        !entity.has::<ComponentId<C, I>>()
+    }
+}
+
+/// Filter entities to ones having component `C` inserted since the previous run of the system.
+#[derive(Debug)]
+pub struct Added<C>(PhantomData<C>);
+
+impl<C: 'static> QueryFilter for Added<C> {
+    fn matches<I: Identifier + 'static, V: ?Sized + WorldValue>(
+        entity: &TypeMap<V>,
+        tables: &TablesMut<'_, I, V>,
+        last_run: u32,
+    ) -> bool {
+        component_ticks::<C, I, V>(entity, tables).is_some_and(|ticks| ticks.added > last_run)
+    }
+
+    fn access<I: Identifier + 'static>(access: &mut SystemAccess) {
+        access.read_component::<C, I>();
+    }
+}
+
+/// Filter entities to ones having component `C` inserted or mutably accessed since the
+/// previous run of the system.
+#[derive(Debug)]
+pub struct Changed<C>(PhantomData<C>);
+
+impl<C: 'static> QueryFilter for Changed<C> {
+    fn matches<I: Identifier + 'static, V: ?Sized + WorldValue>(
+        entity: &TypeMap<V>,
+        tables: &TablesMut<'_, I, V>,
+        last_run: u32,
+    ) -> bool {
+        component_ticks::<C, I, V>(entity, tables).is_some_and(|ticks| ticks.changed > last_run)
+    }
+
+    fn access<I: Identifier + 'static>(access: &mut SystemAccess) {
+        access.read_component::<C, I>();
+    }
+}
+
+/// Get the ticks of the entity's component `C`.
+fn component_ticks<'t, C: 'static, I: Identifier + 'static, V: ?Sized + WorldValue>(
+    entity: &TypeMap<V>,
+    tables: &'t TablesMut<'_, I, V>,
+) -> Option<&'t ComponentTicks> {
+    let comp_id = entity.get::<ComponentId<C, I>>()?;
+    tables
+        .peek(TypeId::of::<ComponentId<C, I>>())?
+        .ticks(comp_id.id)
+}
+
+macro_rules! impl_query_filter_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: QueryFilter),+> QueryFilter for ($($name,)+) {
+            fn matches<I: Identifier + 'static, V: ?Sized + WorldValue>(
+                entity: &TypeMap<V>,
+                tables: &TablesMut<'_, I, V>,
+                last_run: u32,
+            ) -> bool {
+                $($name::matches::<I, V>(entity, tables, last_run))&&+
+            }
+
+            fn access<I: Identifier + 'static>(access: &mut SystemAccess) {
+                $($name::access::<I>(access);)+
+            }
+        }
+    };
+}
+
+impl_query_filter_tuple!(F0);
+impl_query_filter_tuple!(F0, F1);
+impl_query_filter_tuple!(F0, F1, F2);
+impl_query_filter_tuple!(F0, F1, F2, F3);
+impl_query_filter_tuple!(F0, F1, F2, F3, F4);
+impl_query_filter_tuple!(F0, F1, F2, F3, F4, F5);
+impl_query_filter_tuple!(F0, F1, F2, F3, F4, F5, F6);
+impl_query_filter_tuple!(F0, F1, F2, F3, F4, F5, F6, F7);
+
+#[cfg(all(test, feature = "slab"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn system_params() {
+        #[derive(Debug, PartialEq)]
+        struct Position(i32);
+        struct Velocity(i32);
+        struct Frozen;
+        struct Gravity(i32);
+        struct Moved(usize);
+
+        fn apply_gravity(
+            query: Query<usize, &mut Velocity, Without<Frozen>>,
+            gravity: Res<Gravity>,
+        ) {
+            let gravity = &*gravity;
+            query.for_each(|(_, velocity)| velocity.0 -= gravity.0);
+        }
+
+        fn movement(
+            query: Query<usize, (&mut Position, &Velocity)>,
+            frozen: Query<usize, &Velocity, With<Frozen>>,
+            mut moved: ResMut<Moved>,
+        ) {
+            assert_eq!(frozen.count(), 1);
+            let moved = &mut *moved;
+            for (_, (position, velocity)) in query {
+                position.0 += velocity.0;
+                moved.0 += 1;
+            }
+        }
+
+        let mut world = World::new();
+        let a = world.spawn().insert(Position(0)).insert(Velocity(0)).id;
+        let b = world
+            .spawn()
+            .insert(Position(10))
+            .insert(Velocity(0))
+            .insert(Frozen)
+            .id;
+        world.insert_resource(Gravity(1));
+        world.insert_resource(Moved(0));
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(Stage::Update, apply_gravity)
+            .add_system(Stage::Update, movement);
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(world.component_mut::<Position>(a), Some(&mut Position(-3)));
+        assert_eq!(world.component_mut::<Position>(b), Some(&mut Position(10)));
+        assert_eq!(world.resource::<Moved>().unwrap().0, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Resource accessed mutably more than once")]
+    fn system_conflicting_resource_access_panics() {
+        struct Gravity(#[allow(dead_code)] i32);
+
+        let mut world = World::new();
+        world.insert_resource(Gravity(1));
+        let mut schedule = Schedule::new();
+        schedule.add_system(Stage::Update, |_: Res<Gravity>, _: ResMut<Gravity>| {});
+        schedule.run(&mut world);
+    }
+
+    #[test]
+    fn added_and_changed_system_filters() {
+        struct Position(i32);
+        struct Velocity(i32);
+        #[derive(Debug, Default, PartialEq)]
+        struct Seen {
+            added: usize,
+            changed: usize,
+        }
+
+        fn movement(query: Query<usize, (&mut Position, &Velocity)>) {
+            query
+                .filter(|(_, (_, velocity))| velocity.0 != 0)
+                .for_each(|(_, (position, velocity))| position.0 += velocity.0);
+        }
+
+        fn track(
+            added: Query<usize, &Position, Added<Position>>,
+            changed: Query<usize, &Position, Changed<Position>>,
+            mut seen: ResMut<Seen>,
+        ) {
+            *seen = Seen {
+                added: added.count(),
+                changed: changed.count(),
+            };
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Seen::default());
+        world.spawn().insert(Position(0)).insert(Velocity(1));
+        world.spawn().insert(Position(0)).insert(Velocity(0));
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(Stage::Update, movement)
+            .add_system(Stage::PostUpdate, track);
+
+        // All entities are fetched mutably by `movement`, hence changed...
+        schedule.run(&mut world);
+        assert_eq!(
+            world.resource::<Seen>(),
+            Some(&Seen {
+                added: 2,
+                changed: 2
+            })
+        );
+
+        world.spawn().insert(Position(0)).insert(Velocity(0));
+        schedule.run(&mut world);
+        assert_eq!(
+            world.resource::<Seen>(),
+            Some(&Seen {
+                added: 1,
+                changed: 3
+            })
+        );
+    }
+}
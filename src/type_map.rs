@@ -3,6 +3,80 @@ use std::{
     collections::HashMap,
 };
 
+mod private {
+    use core::any::Any;
+
+    pub trait Sealed {}
+
+    impl Sealed for dyn Any {}
+    impl Sealed for dyn Any + Send + Sync {}
+}
+
+/// Type-erased values storable in a [`TypeMap`], ie. `dyn Any` or `dyn Any + Send + Sync`.
+///
+/// Sealed, implemented only for the above.
+pub trait AnyValue: Any + private::Sealed {
+    /// Type bounding values to be `Send + Sync` (if required), ie. `()` for `dyn Any` and `T`
+    /// for `dyn Any + Send + Sync`.
+    ///
+    /// Used as `AnyValue::ThreadSafe<T>: Send + Sync` where clause on methods storing values.
+    type ThreadSafe<T>;
+
+    /// Box the value.
+    fn erase<T: 'static>(t: T) -> Box<Self>
+    where
+        Self::ThreadSafe<T>: Send + Sync;
+
+    /// Get ref to the value as `dyn Any`.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Get (mutable) ref to the value as `dyn Any`.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Convert the box into `Box<dyn Any>`.
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl AnyValue for dyn Any {
+    type ThreadSafe<T> = ();
+
+    fn erase<T: 'static>(t: T) -> Box<Self> {
+        Box::new(t)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl AnyValue for dyn Any + Send + Sync {
+    type ThreadSafe<T> = T;
+
+    fn erase<T: Send + Sync + 'static>(t: T) -> Box<Self> {
+        Box::new(t)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
 /// Map types to its associated values.
 ///
 /// - Internal Data Structure for dynamic mapping of Types to its values,
@@ -48,8 +122,35 @@ use std::{
 /// assert_eq!(type_map.get::<HP>(), Some(&HP(100)));
 /// ```
 ///
-#[derive(Debug, Default)]
-pub struct TypeMap(HashMap<TypeId, Box<dyn Any>>);
+/// ## Thread-safe
+///
+/// Use [`SyncTypeMap`] to only store values that are `Send + Sync`, making the map itself
+/// `Send + Sync`.
+///
+/// ```
+/// # use anvaya::prelude::SyncTypeMap;
+/// let mut type_map = SyncTypeMap::default();
+/// type_map.insert::<i32>(1);
+/// // type_map.insert(std::rc::Rc::new(1)); // Does not compile, `Rc` is not `Send`.
+/// let type_map = std::thread::spawn(move || type_map).join().unwrap();
+/// assert_eq!(type_map.get::<i32>(), Some(&1));
+/// ```
+///
+#[derive(Debug)]
+pub struct TypeMap<V: ?Sized + AnyValue = dyn Any>(HashMap<TypeId, Box<V>>);
+
+/// [`AnyValue::ThreadSafe`] of `V`, used as `ThreadSafe<V, T>: Send + Sync` where clause on
+/// methods storing values of type `T`.
+pub type ThreadSafe<V, T> = <V as AnyValue>::ThreadSafe<T>;
+
+/// [`TypeMap`] storing only `Send + Sync` values.
+pub type SyncTypeMap = TypeMap<dyn Any + Send + Sync>;
+
+impl<V: ?Sized + AnyValue> Default for TypeMap<V> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
 
 impl TypeMap {
     /// Create a new empty [`TypeMap`].
@@ -63,7 +164,9 @@ impl TypeMap {
     pub fn with_capacity(capacity: usize) -> Self {
         Self(HashMap::with_capacity(capacity))
     }
+}
 
+impl<V: ?Sized + AnyValue> TypeMap<V> {
     /// Insert a value.
     ///
     /// # Examples
@@ -73,8 +176,38 @@ impl TypeMap {
     /// let mut type_map = TypeMap::new();
     /// type_map.insert::<i32>(1); // Prefer specifying the type as generic to prevent wrong type inference.
     /// ```
-    pub fn insert<T: Any + 'static>(&mut self, t: T) {
-        self.0.insert(TypeId::of::<T>(), Box::new(t));
+    pub fn insert<T: Any + 'static>(&mut self, t: T)
+    where
+        ThreadSafe<V, T>: Send + Sync,
+    {
+        self.0.insert(TypeId::of::<T>(), V::erase(t));
+    }
+
+    /// Reserve capacity for at least `additional` more values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anvaya::prelude::TypeMap;
+    /// let mut type_map = TypeMap::new();
+    /// type_map.reserve(4);
+    /// type_map.insert::<i32>(1);
+    /// assert_eq!(type_map.len(), 1);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional);
+    }
+
+    /// Insert a boxed value for the given type.
+    pub(crate) fn insert_boxed(&mut self, type_id: TypeId, value: Box<V>) {
+        self.0.insert(type_id, value);
+    }
+
+    /// Iterate over `(TypeId, &mut value)` pairs, without erasing the values to `dyn Any`.
+    pub(crate) fn iter_mut_erased(&mut self) -> impl Iterator<Item = (TypeId, &mut V)> {
+        self.0
+            .iter_mut()
+            .map(|(type_id, value)| (*type_id, &mut **value))
     }
 
     /// Returns `true` if the [`TypeMap`] _has_ the given type.
@@ -130,7 +263,7 @@ impl TypeMap {
     pub fn get<T: Any + 'static>(&self) -> Option<&T> {
         self.0
             .get(&TypeId::of::<T>())
-            .map(|t| t.downcast_ref::<T>().unwrap())
+            .map(|t| t.as_any().downcast_ref::<T>().unwrap())
     }
 
     /// Get (mutable) ref to stored value for a given type.
@@ -148,7 +281,64 @@ impl TypeMap {
     pub fn get_mut<T: Any + 'static>(&mut self) -> Option<&mut T> {
         self.0
             .get_mut(&TypeId::of::<T>())
-            .map(|t| t.downcast_mut::<T>().unwrap())
+            .map(|t| t.as_any_mut().downcast_mut::<T>().unwrap())
+    }
+
+    /// Remove the stored value for a given type and return it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anvaya::prelude::TypeMap;
+    /// let mut type_map = TypeMap::new();
+    /// type_map.insert::<i32>(1);
+    /// assert_eq!(type_map.remove::<i32>(), Some(1));
+    /// assert_eq!(type_map.remove::<i32>(), None);
+    /// ```
+    pub fn remove<T: Any + 'static>(&mut self) -> Option<T> {
+        self.0
+            .remove(&TypeId::of::<T>())
+            .map(|t| *t.into_any().downcast::<T>().unwrap())
+    }
+
+    /// Iterate over `(TypeId, &value)` pairs of the [`TypeMap`].
+    ///
+    /// Values are type-erased, downcast them using the paired `TypeId`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::any::TypeId;
+    /// # use anvaya::prelude::TypeMap;
+    /// let mut type_map = TypeMap::new();
+    /// type_map.insert::<i32>(1);
+    /// let (type_id, value) = type_map.iter().next().unwrap();
+    /// assert_eq!(type_id, TypeId::of::<i32>());
+    /// assert_eq!(value.downcast_ref::<i32>(), Some(&1));
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (TypeId, &dyn Any)> {
+        self.0
+            .iter()
+            .map(|(type_id, value)| (*type_id, value.as_any()))
+    }
+
+    /// Iterate over `(TypeId, &mut value)` pairs of the [`TypeMap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anvaya::prelude::TypeMap;
+    /// let mut type_map = TypeMap::new();
+    /// type_map.insert::<i32>(1);
+    /// for (_, value) in type_map.iter_mut() {
+    ///     *value.downcast_mut::<i32>().unwrap() += 1;
+    /// }
+    /// assert_eq!(type_map.get::<i32>(), Some(&2));
+    /// ```
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (TypeId, &mut dyn Any)> {
+        self.0
+            .iter_mut()
+            .map(|(type_id, value)| (*type_id, value.as_any_mut()))
     }
 
     /// Clear the [`TypeMap`].
@@ -1,26 +1,158 @@
-use core::marker::PhantomData;
+use core::{
+    any::{Any, TypeId},
+    fmt::Debug,
+    marker::PhantomData,
+};
+use std::collections::{HashMap, hash_map::Entry};
 
 use crate::{
+    bundle::Bundle,
     entity_builder::EntityBuilderMethods,
+    events::AllEvents,
     query_builder::QueryBuilderMethods,
+    query_mut_builder::QueryMutBuilderMethods,
     storage::{Identifier, Storage},
-    type_map::TypeMap,
+    type_map::{AnyValue, ThreadSafe, TypeMap},
 };
 
+/// World of entities stored in `E`, as [`TypeMap`]s of their component ids.
+///
+/// The values of the entity [`TypeMap`] determine the flavour of the world, see [`WorldValue`],
+/// eg. `World<usize, Slab<SyncTypeMap>>` is `Send + Sync`.
 #[derive(Debug, Default)]
-pub struct World<I: Identifier, E: Storage<Key = I, Value = TypeMap>> {
+pub struct World<I: Identifier + 'static, E: Storage<Key = I, Value: EntityTypeMap>> {
     pub(crate) entities: Entities<E>,
-    pub(crate) all_tables: AllTables,
+    pub(crate) all_tables: AllTables<I, Erased<E>>,
+    pub(crate) resources: Resources<Erased<E>>,
+    pub(crate) ticks: WorldTicks,
+    pub(crate) removed: RemovedComponents<I>,
+    pub(crate) events: AllEvents<Erased<E>>,
+}
+
+/// Type-erased values of the world with entity storage `E`.
+type Erased<E> = <<E as Storage>::Value as EntityTypeMap>::Erased;
+
+/// Type-erased values stored in a [`World`], ie. its components, resources and events.
+///
+/// - `dyn Any`: the default, stores any `'static` values.
+/// - `dyn Any + Send + Sync`: only stores `Send + Sync` values, making the world `Send + Sync`.
+pub trait WorldValue: AnyValue + Debug {
+    /// Type-erased component [`Table`].
+    type Table<I: Identifier + 'static>: ?Sized + AnyTable<I> + Debug;
+
+    /// Get ref to the table as `dyn AnyTable`.
+    fn table_ref<I: Identifier + 'static>(table: &Self::Table<I>) -> &dyn AnyTable<I>;
+
+    /// Get (mutable) ref to the table as `dyn AnyTable`.
+    fn table_mut<I: Identifier + 'static>(table: &mut Self::Table<I>) -> &mut dyn AnyTable<I>;
+}
+
+impl WorldValue for dyn Any {
+    type Table<I: Identifier + 'static> = dyn AnyTable<I>;
+
+    fn table_ref<I: Identifier + 'static>(table: &Self::Table<I>) -> &dyn AnyTable<I> {
+        table
+    }
+
+    fn table_mut<I: Identifier + 'static>(table: &mut Self::Table<I>) -> &mut dyn AnyTable<I> {
+        table
+    }
+}
+
+impl WorldValue for dyn Any + Send + Sync {
+    type Table<I: Identifier + 'static> = dyn AnyTable<I> + Send + Sync;
+
+    fn table_ref<I: Identifier + 'static>(table: &Self::Table<I>) -> &dyn AnyTable<I> {
+        table
+    }
+
+    fn table_mut<I: Identifier + 'static>(table: &mut Self::Table<I>) -> &mut dyn AnyTable<I> {
+        table
+    }
+}
+
+/// Entity [`TypeMap`] of a [`World`], its values determine the world's [`WorldValue`].
+pub trait EntityTypeMap {
+    /// Type-erased values of the world.
+    type Erased: ?Sized + WorldValue;
+}
+
+impl<V: ?Sized + WorldValue> EntityTypeMap for TypeMap<V> {
+    type Erased = V;
+}
+
+/// Component storage able to create its type-erased [`Table`] in worlds storing `V`.
+///
+/// Implemented for all storages, requiring them (and their keys) to be `Send + Sync` for
+/// thread-safe worlds.
+pub trait TableStorage<V: ?Sized + WorldValue>:
+    Storage<Key: 'static, Value: 'static> + 'static
+{
+    /// Create an empty type-erased [`Table`] using the storage, with pre-allocated capacity.
+    fn new_table(capacity: usize) -> Box<V::Table<Self::Key>>;
+
+    /// Box the key of a component in the table, to be stored in its entity's [`TypeMap`].
+    fn erase_component_id(id: Self::Key) -> Box<V>;
+}
+
+impl<S: Storage<Key: 'static, Value: 'static> + 'static> TableStorage<dyn Any> for S {
+    fn new_table(capacity: usize) -> Box<dyn AnyTable<S::Key>> {
+        Box::new(Table::<S::Value, S::Key, S>::with_capacity(capacity))
+    }
+
+    fn erase_component_id(id: Self::Key) -> Box<dyn Any> {
+        Box::new(ComponentId::<S::Value, S::Key> {
+            id,
+            _phantom_data: PhantomData,
+        })
+    }
+}
+
+impl<S: Storage<Key: Send + Sync + 'static, Value: 'static> + Send + Sync + 'static>
+    TableStorage<dyn Any + Send + Sync> for S
+{
+    fn new_table(capacity: usize) -> Box<dyn AnyTable<S::Key> + Send + Sync> {
+        Box::new(Table::<S::Value, S::Key, S>::with_capacity(capacity))
+    }
+
+    fn erase_component_id(id: Self::Key) -> Box<dyn Any + Send + Sync> {
+        Box::new(ComponentId::<S::Value, S::Key> {
+            id,
+            _phantom_data: PhantomData,
+        })
+    }
 }
 
 pub trait WorldMethods: Default {
     type Key: Identifier + 'static;
-    type EntityStorage: Storage<Key = Self::Key, Value = TypeMap>;
-    type ComponentStorage<T: 'static>: Storage<Key = Self::Key, Value = T> + 'static;
-    type AssocEntityBuilder<'a>: EntityBuilderMethods<'a, Key = Self::Key, EntityStorage = Self::EntityStorage>
+    type Erased: ?Sized + WorldValue;
+    type EntityStorage: Storage<Key = Self::Key, Value = TypeMap<Self::Erased>>;
+    type ComponentStorage<T: 'static>: Storage<Key = Self::Key, Value = T>
+        + TableStorage<Self::Erased>
+    where
+        ThreadSafe<Self::Erased, T>: Send + Sync;
+    type AssocEntityBuilder<'a>: EntityBuilderMethods<
+            'a,
+            Key = Self::Key,
+            Erased = Self::Erased,
+            EntityStorage = Self::EntityStorage,
+        >
+    where
+        Self: 'a;
+    type AssocQueryBuilder<'a>: QueryBuilderMethods<
+            'a,
+            Key = Self::Key,
+            Erased = Self::Erased,
+            EntityStorage = Self::EntityStorage,
+        >
     where
         Self: 'a;
-    type AssocQueryBuilder<'a>: QueryBuilderMethods<'a, Key = Self::Key, EntityStorage = Self::EntityStorage>
+    type AssocQueryMutBuilder<'a>: QueryMutBuilderMethods<
+            'a,
+            Key = Self::Key,
+            Erased = Self::Erased,
+            EntityStorage = Self::EntityStorage,
+        >
     where
         Self: 'a;
 
@@ -33,50 +165,723 @@ pub trait WorldMethods: Default {
     fn world_mut(&mut self) -> &mut World<Self::Key, Self::EntityStorage>;
 
     fn spawn<'a>(&'a mut self) -> Self::AssocEntityBuilder<'a> {
-        let id = self.world_mut().entities.0.insert(TypeMap::new());
+        let id = self.world_mut().entities.0.insert(TypeMap::default());
         Self::AssocEntityBuilder::create(id, self.world_mut())
     }
 
+    /// Spawn an entity with the components of the bundle, see [`Bundle`].
+    fn spawn_bundle<'a, B: Bundle<Self::Erased>>(
+        &'a mut self,
+        bundle: B,
+    ) -> Self::AssocEntityBuilder<'a> {
+        let mut builder = self.spawn();
+        builder.insert_bundle(bundle);
+
+        builder
+    }
+
+    /// Spawn an entity for each of the bundles, returning their keys in order.
+    ///
+    /// Reserves capacity for the entities (using the lower bound of the iterator's size hint)
+    /// in the entity storage and the tables of the bundle's components upfront.
+    fn spawn_batch<B: Bundle<Self::Erased>>(
+        &mut self,
+        bundles: impl IntoIterator<Item = B>,
+    ) -> Vec<Self::Key> {
+        let bundles = bundles.into_iter();
+        let (additional, _) = bundles.size_hint();
+        let world = self.world_mut();
+        world.entities.0.reserve(additional);
+        B::reserve_tables::<Self::AssocEntityBuilder<'_>>(world, additional);
+
+        bundles
+            .map(|bundle| self.spawn_bundle(bundle).id())
+            .collect()
+    }
+
     fn query<'a>(&'a self) -> Self::AssocQueryBuilder<'a> {
         Self::AssocQueryBuilder::create(self.world())
     }
 
+    fn query_mut<'a>(&'a mut self) -> Self::AssocQueryMutBuilder<'a> {
+        Self::AssocQueryMutBuilder::create(self.world_mut())
+    }
+
+    /// Get ref to component `C` of the entity.
+    fn component<C: 'static>(&self, entity: Self::Key) -> Option<&C> {
+        let world = self.world();
+        let entity_comp_ids = world.entities.0.get(entity)?;
+        let component_id = entity_comp_ids.get::<ComponentId<C, Self::Key>>()?;
+        let table = world
+            .all_tables
+            .get_erased(TypeId::of::<ComponentId<C, Self::Key>>())?;
+
+        table.get_erased(component_id.id)?.downcast_ref::<C>()
+    }
+
+    /// Get (mutable) ref to component `C` of the entity, marking it as changed.
     fn component_mut<C: 'static>(&mut self, entity: Self::Key) -> Option<&mut C> {
         let world = self.world_mut();
         let entity_comp_ids = world.entities.0.get(entity)?;
         let component_id = entity_comp_ids.get::<ComponentId<C, Self::Key>>()?;
         let table = world
             .all_tables
-            .0
-            .get_mut::<Table<C, Self::Key, Self::ComponentStorage<C>>>()?;
+            .get_erased_mut(TypeId::of::<ComponentId<C, Self::Key>>())?;
 
-        table.storage.get_mut(component_id.id)
+        table
+            .get_mut_erased(component_id.id, world.ticks.change_tick)?
+            .downcast_mut::<C>()
+    }
+
+    /// Remove component `C` from the entity and return it.
+    ///
+    /// Returns `None` if the entity does not exist or does not have the component.
+    fn remove_component<C: 'static>(&mut self, entity: Self::Key) -> Option<C> {
+        let world = self.world_mut();
+        let entity_comp_ids = world.entities.0.get_mut(entity)?;
+        let component_id = entity_comp_ids.remove::<ComponentId<C, Self::Key>>()?;
+        let table = world
+            .all_tables
+            .get_erased_mut(TypeId::of::<ComponentId<C, Self::Key>>())?;
+        let mut component = None;
+        table.remove_into(component_id.id, &mut component);
+        let component = component?;
+        world.removed.record(entity, TypeId::of::<C>());
+
+        Some(component)
+    }
+
+    /// Insert resource `R`, returning the previous value if there was one.
+    fn insert_resource<R: 'static>(&mut self, resource: R) -> Option<R>
+    where
+        ThreadSafe<Self::Erased, R>: Send + Sync,
+    {
+        let resources = &mut self.world_mut().resources.0;
+        let previous = resources.remove::<R>();
+        resources.insert::<R>(resource);
+
+        previous
+    }
+
+    /// Get ref to resource `R`.
+    fn resource<R: 'static>(&self) -> Option<&R> {
+        self.world().resources.0.get::<R>()
+    }
+
+    /// Get (mutable) ref to resource `R`.
+    fn resource_mut<R: 'static>(&mut self) -> Option<&mut R> {
+        self.world_mut().resources.0.get_mut::<R>()
+    }
+
+    /// Remove resource `R` and return it.
+    fn remove_resource<R: 'static>(&mut self) -> Option<R> {
+        self.world_mut().resources.0.remove::<R>()
+    }
+
+    /// Send event `E`, readable by an [`EventReader`](crate::prelude::EventReader).
+    fn send_event<E: 'static>(&mut self, event: E)
+    where
+        ThreadSafe<Self::Erased, E>: Send + Sync,
+    {
+        self.world_mut().events.send(event);
+    }
+
+    /// Swap the event buffers, dropping events sent before the previous call.
+    ///
+    /// Usually called once per frame.
+    fn update_events(&mut self) {
+        self.world_mut().events.update();
+    }
+
+    /// Despawn the entity, freeing all of its components from their tables.
+    ///
+    /// Returns `false` if the entity did not exist.
+    fn despawn(&mut self, entity: Self::Key) -> bool {
+        let world = self.world_mut();
+        let Some(entity_comp_ids) = world.entities.0.remove(entity) else {
+            return false;
+        };
+        for (type_id, component_id) in entity_comp_ids.iter() {
+            if let Some(table) = world.all_tables.get_erased_mut(type_id) {
+                table.remove_erased(component_id);
+                world.removed.record(entity, table.component_type_id());
+            }
+        }
+
+        true
+    }
+
+    /// Drain the entities component `C` was removed from, either via
+    /// [`WorldMethods::remove_component`] or by despawning the entity.
+    ///
+    /// Removals are kept until drained, or dropped on the second
+    /// [`WorldMethods::update_removed`] call after they were recorded.
+    fn removed<C: 'static>(&mut self) -> Vec<Self::Key> {
+        self.world_mut().removed.drain(TypeId::of::<C>())
+    }
+
+    /// Swap the removal buffers, dropping [`WorldMethods::removed`] events recorded before the
+    /// previous call and not drained since.
+    ///
+    /// Usually called once per frame.
+    fn update_removed(&mut self) {
+        self.world_mut().removed.update();
+    }
+
+    /// Current change tick, stamped on components when inserted or mutably accessed.
+    fn change_tick(&self) -> u32 {
+        self.world().ticks.change_tick
+    }
+
+    /// Advance the change tick, returning the previous one.
+    ///
+    /// Changes made after the call are newer than the returned tick, hence it can be kept as the
+    /// baseline of the `added_since` and `changed_since` query builder filters, independent of
+    /// [`WorldMethods::clear_trackers`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anvaya::prelude::*;
+    /// struct Position(i32);
+    ///
+    /// let mut world = World::new();
+    /// let a = world.spawn().insert(Position(0)).id;
+    /// let last_run = world.advance_change_tick();
+    /// world.clear_trackers();
+    /// world.component_mut::<Position>(a).unwrap().0 = 1;
+    ///
+    /// let mut query = world.query();
+    /// assert_eq!(query.changed_since::<Position>(last_run).get::<&Position>().unwrap().count(), 1);
+    /// let last_run = world.advance_change_tick();
+    /// let mut query = world.query();
+    /// assert_eq!(query.changed_since::<Position>(last_run).get::<&Position>().unwrap().count(), 0);
+    /// ```
+    fn advance_change_tick(&mut self) -> u32 {
+        self.world_mut().ticks.increment()
+    }
+
+    /// Start tracking changes anew.
+    ///
+    /// The `added` and `changed` query filters match components inserted or mutably accessed
+    /// since the last call, usually made once per frame after processing the changes. This
+    /// baseline is shared by all builder queries.
+    fn clear_trackers(&mut self) {
+        let world = self.world_mut();
+        world.ticks.last_change_tick = world.ticks.change_tick;
+        world.ticks.change_tick += 1;
     }
 }
 
 #[derive(Debug, Default)]
 pub(crate) struct Entities<S: Storage>(pub(crate) S);
 
+/// Change ticks of the world.
+#[derive(Debug)]
+pub(crate) struct WorldTicks {
+    /// Tick stamped on inserted and mutated components.
+    pub(crate) change_tick: u32,
+    /// Tick of the last [`WorldMethods::clear_trackers`] call.
+    pub(crate) last_change_tick: u32,
+}
+
+impl WorldTicks {
+    /// Advance the change tick, returning the previous one.
+    ///
+    /// Used as the tick of a system run, so later changes (made outside the system) are newer.
+    pub(crate) fn increment(&mut self) -> u32 {
+        self.change_tick += 1;
+        self.change_tick - 1
+    }
+}
+
+impl Default for WorldTicks {
+    fn default() -> Self {
+        // Ahead of the last change tick, so initial insertions count as added...
+        Self {
+            change_tick: 1,
+            last_change_tick: 0,
+        }
+    }
+}
+
+/// Double buffered removal events of components, keyed by the component's `TypeId`.
+///
+/// Events live for two [`WorldMethods::update_removed`] calls unless drained, so ones never
+/// drained do not accumulate.
 #[derive(Debug, Default)]
-pub(crate) struct AllTables(pub(crate) TypeMap);
+pub(crate) struct RemovedComponents<I: Identifier> {
+    /// Events recorded before the last update.
+    previous: HashMap<TypeId, Vec<I>>,
+    /// Events recorded since the last update.
+    current: HashMap<TypeId, Vec<I>>,
+}
+
+impl<I: Identifier> RemovedComponents<I> {
+    fn record(&mut self, entity: I, component_type: TypeId) {
+        self.current.entry(component_type).or_default().push(entity);
+    }
+
+    fn drain(&mut self, component_type: TypeId) -> Vec<I> {
+        let mut previous = self.previous.remove(&component_type).unwrap_or_default();
+        previous.extend(self.current.remove(&component_type).unwrap_or_default());
+        previous
+    }
+
+    /// Swap the buffers, dropping the events recorded before the previous update.
+    fn update(&mut self) {
+        self.previous = core::mem::take(&mut self.current);
+    }
+}
+
+/// Global singleton values of the world, one per type.
+#[derive(Debug)]
+pub(crate) struct Resources<V: ?Sized + WorldValue>(pub(crate) TypeMap<V>);
+
+impl<V: ?Sized + WorldValue> Default for Resources<V> {
+    fn default() -> Self {
+        Self(TypeMap::default())
+    }
+}
+
+/// All component [`Table`]s, keyed by the `TypeId` of the [`ComponentId`] pointing into them.
+///
+/// Keying by [`ComponentId`] allows looking up a table from an entity's [`TypeMap`] entry
+/// without knowing the component type.
+#[derive(Debug)]
+pub(crate) struct AllTables<I: Identifier + 'static, V: ?Sized + WorldValue>(
+    pub(crate) HashMap<TypeId, Box<V::Table<I>>>,
+);
+
+impl<I: Identifier + 'static, V: ?Sized + WorldValue> Default for AllTables<I, V> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<I: Identifier + 'static, V: ?Sized + WorldValue> AllTables<I, V> {
+    pub(crate) fn get_or_insert<C: 'static, T: Storage<Key = I, Value = C> + TableStorage<V>>(
+        &mut self,
+    ) -> &mut Table<C, I, T> {
+        let table: &mut dyn Any = V::table_mut(
+            self.0
+                .entry(TypeId::of::<ComponentId<C, I>>())
+                .or_insert_with(|| T::new_table(0)),
+        );
+        table
+            .downcast_mut::<Table<C, I, T>>()
+            .expect("Table type mismatch for component")
+    }
+
+    /// Reserve capacity for at least `additional` more components `C`, creating the table
+    /// with the capacity if it does not exist.
+    pub(crate) fn reserve<C: 'static, T: Storage<Key = I, Value = C> + TableStorage<V>>(
+        &mut self,
+        additional: usize,
+    ) {
+        match self.0.entry(TypeId::of::<ComponentId<C, I>>()) {
+            Entry::Occupied(mut table) => V::table_mut(table.get_mut()).reserve(additional),
+            Entry::Vacant(entry) => {
+                entry.insert(T::new_table(additional));
+            }
+        }
+    }
+
+    /// Get type-erased table using the `TypeId` of a [`ComponentId`].
+    pub(crate) fn get_erased(&self, component_id_type: TypeId) -> Option<&dyn AnyTable<I>> {
+        self.0
+            .get(&component_id_type)
+            .map(|table| V::table_ref(table))
+    }
+
+    /// Get type-erased (mutable) table using the `TypeId` of a [`ComponentId`].
+    pub(crate) fn get_erased_mut(
+        &mut self,
+        component_id_type: TypeId,
+    ) -> Option<&mut dyn AnyTable<I>> {
+        self.0
+            .get_mut(&component_id_type)
+            .map(|table| V::table_mut(table))
+    }
+}
+
+/// Iterator over `(component key, &mut component)` of a type-erased table.
+type ErasedIterMut<'a, I> = Box<dyn Iterator<Item = (I, &'a mut dyn Any)> + 'a>;
+
+/// Type-erased operations on a [`Table`], used when the component type is not known.
+pub trait AnyTable<I: Identifier>: Any {
+    /// Get the component for the given component key.
+    fn get_erased(&self, id: I) -> Option<&dyn Any>;
+
+    /// Get (mutable) ref to the component for the given component key, marking it as changed at
+    /// the change tick.
+    fn get_mut_erased(&mut self, id: I, change_tick: u32) -> Option<&mut dyn Any>;
+
+    /// Split the table into an iterator over `(component key, &mut component)` and the ticks
+    /// of the components keyed by their component key.
+    fn split_mut_erased(&mut self) -> (ErasedIterMut<'_, I>, &mut HashMap<I, ComponentTicks>);
+
+    /// Get the component pointed to by the type-erased [`ComponentId`].
+    fn get_by_component_id(&self, component_id: &dyn Any) -> Option<&dyn Any>;
+
+    /// Get the ticks of the component for the given component key.
+    fn ticks(&self, id: I) -> Option<&ComponentTicks>;
+
+    /// Remove the component pointed to by the type-erased [`ComponentId`].
+    fn remove_erased(&mut self, component_id: &dyn Any);
+
+    /// Remove the component for the given component key, moving it into `slot` (an
+    /// `Option<C>`) without boxing it.
+    fn remove_into(&mut self, id: I, slot: &mut dyn Any);
+
+    /// `TypeId` of the component stored in the table.
+    fn component_type_id(&self) -> TypeId;
+
+    /// Type name of the component stored in the table, see [`core::any::type_name`].
+    fn component_type_name(&self) -> &'static str;
+
+    /// Reserve capacity for at least `additional` more components.
+    fn reserve(&mut self, additional: usize);
+}
+
+impl<I: Identifier> Debug for dyn AnyTable<I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AnyTable").finish_non_exhaustive()
+    }
+}
+
+impl<I: Identifier> Debug for dyn AnyTable<I> + Send + Sync {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AnyTable").finish_non_exhaustive()
+    }
+}
 
 #[derive(Debug)]
 pub(crate) struct ComponentId<C, I: Identifier> {
     pub(crate) id: I,
-    pub(crate) _phantom_data: PhantomData<C>,
+    pub(crate) _phantom_data: PhantomData<fn() -> C>,
+}
+
+/// Change ticks of a component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentTicks {
+    /// Tick the component was inserted at.
+    pub added: u32,
+    /// Tick the component was last inserted or mutably accessed at.
+    pub changed: u32,
+}
+
+impl ComponentTicks {
+    fn new(change_tick: u32) -> Self {
+        Self {
+            added: change_tick,
+            changed: change_tick,
+        }
+    }
 }
 
 #[derive(Debug, Default)]
 pub(crate) struct Table<C, I: Identifier, T: Storage<Key = I, Value = C>> {
     pub(crate) storage: T,
-    _phantom_data: PhantomData<C>,
+    /// Change ticks by component key.
+    pub(crate) ticks: HashMap<I, ComponentTicks>,
+    _phantom_data: PhantomData<fn() -> C>,
 }
 
 impl<C, I: Identifier, T: Storage<Key = I, Value = C>> Table<C, I, T> {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
         Self {
-            storage: T::default(),
+            storage: T::with_capacity(capacity),
+            ticks: HashMap::with_capacity(capacity),
             _phantom_data: PhantomData,
         }
     }
+
+    /// Insert the component as added at the change tick, returning its key.
+    pub(crate) fn insert(&mut self, component: C, change_tick: u32) -> I {
+        let id = self.storage.insert(component);
+        self.ticks.insert(id, ComponentTicks::new(change_tick));
+
+        id
+    }
+
+    /// Get (mutable) ref to the component, marking it as changed at the change tick.
+    pub(crate) fn get_mut(&mut self, id: I, change_tick: u32) -> Option<&mut C> {
+        let component = self.storage.get_mut(id)?;
+        if let Some(ticks) = self.ticks.get_mut(&id) {
+            ticks.changed = change_tick;
+        }
+
+        Some(component)
+    }
+
+    /// Remove the component along with its ticks.
+    pub(crate) fn remove(&mut self, id: I) -> Option<C> {
+        self.ticks.remove(&id);
+        self.storage.remove(id)
+    }
+}
+
+impl<C: 'static, I: Identifier + 'static, T: Storage<Key = I, Value = C> + 'static> AnyTable<I>
+    for Table<C, I, T>
+{
+    fn get_erased(&self, id: I) -> Option<&dyn Any> {
+        self.storage.get(id).map(|component| component as &dyn Any)
+    }
+
+    fn get_mut_erased(&mut self, id: I, change_tick: u32) -> Option<&mut dyn Any> {
+        self.get_mut(id, change_tick)
+            .map(|component| component as &mut dyn Any)
+    }
+
+    fn split_mut_erased(&mut self) -> (ErasedIterMut<'_, I>, &mut HashMap<I, ComponentTicks>) {
+        let components = self
+            .storage
+            .iter_mut()
+            .map(|(id, component)| (id, component as &mut dyn Any));
+        (Box::new(components), &mut self.ticks)
+    }
+
+    fn get_by_component_id(&self, component_id: &dyn Any) -> Option<&dyn Any> {
+        let component_id = component_id.downcast_ref::<ComponentId<C, I>>()?;
+        self.get_erased(component_id.id)
+    }
+
+    fn ticks(&self, id: I) -> Option<&ComponentTicks> {
+        self.ticks.get(&id)
+    }
+
+    fn remove_erased(&mut self, component_id: &dyn Any) {
+        if let Some(component_id) = component_id.downcast_ref::<ComponentId<C, I>>() {
+            self.remove(component_id.id);
+        }
+    }
+
+    fn remove_into(&mut self, id: I, slot: &mut dyn Any) {
+        if let Some(slot) = slot.downcast_mut::<Option<C>>() {
+            *slot = self.remove(id);
+        }
+    }
+
+    fn component_type_id(&self) -> TypeId {
+        TypeId::of::<C>()
+    }
+
+    fn component_type_name(&self) -> &'static str {
+        core::any::type_name::<C>()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.storage.reserve(additional);
+        self.ticks.reserve(additional);
+    }
+}
+
+#[cfg(all(test, feature = "slab"))]
+pub(crate) mod tests {
+    use core::any::{Any, TypeId};
+
+    use slab::Slab;
+
+    use super::{ComponentId, Table};
+    use crate::prelude::*;
+
+    /// Storage of the table of component `C`, via its type-erased table.
+    pub(crate) fn storage<C: 'static>(world: &World<usize, Slab<TypeMap>>) -> Option<&Slab<C>> {
+        let table: &dyn Any = world
+            .all_tables
+            .get_erased(TypeId::of::<ComponentId<C, usize>>())?;
+        let table = table.downcast_ref::<Table<C, usize, Slab<C>>>()?;
+
+        Some(&table.storage)
+    }
+
+    #[test]
+    fn resources() {
+        #[derive(Debug, PartialEq)]
+        struct FrameTime(f32);
+        #[derive(Debug, PartialEq)]
+        struct Config {
+            gravity: f32,
+        }
+
+        let mut world = World::new();
+        assert_eq!(world.resource::<FrameTime>(), None);
+        assert_eq!(world.insert_resource(FrameTime(0.016)), None);
+        world.insert_resource(Config { gravity: 9.8 });
+        assert_eq!(
+            world.insert_resource(FrameTime(0.033)),
+            Some(FrameTime(0.016))
+        );
+
+        world.resource_mut::<Config>().unwrap().gravity = 1.6;
+        assert_eq!(world.resource::<Config>(), Some(&Config { gravity: 1.6 }));
+        assert_eq!(world.remove_resource::<FrameTime>(), Some(FrameTime(0.033)));
+        assert_eq!(world.remove_resource::<FrameTime>(), None);
+
+        // Resources are not entities...
+        let mut query = world.query();
+        assert!(query.get::<&Config>().is_none());
+    }
+
+    #[test]
+    fn removed_components_are_tracked() {
+        struct Position(#[allow(dead_code)] i32);
+        struct Velocity(#[allow(dead_code)] i32);
+
+        let mut world = World::new();
+        let a = world.spawn().insert(Position(0)).insert(Velocity(1)).id;
+        let b = world.spawn().insert(Position(1)).id;
+        assert!(world.removed::<Position>().is_empty());
+
+        world.remove_component::<Velocity>(a);
+        // Not recorded as the entity does not have it...
+        world.remove_component::<Velocity>(b);
+        world.despawn(a);
+
+        assert_eq!(world.removed::<Position>(), vec![a]);
+        assert_eq!(world.removed::<Velocity>(), vec![a]);
+        // Drained...
+        assert!(world.removed::<Velocity>().is_empty());
+
+        // Deferred removals are recorded when applied...
+        let mut commands = Commands::new();
+        commands.despawn(b);
+        commands.apply(&mut world);
+        assert_eq!(world.removed::<Position>(), vec![b]);
+
+        // Undrained events are dropped on the second update, independent of change tracking...
+        let c = world.spawn().insert(Position(2)).id;
+        let d = world.spawn().insert(Position(3)).id;
+        world.despawn(c);
+        world.update_removed();
+        world.clear_trackers();
+        world.despawn(d);
+        assert_eq!(world.removed::<Position>(), vec![c, d]);
+        let e = world.spawn().insert(Position(4)).id;
+        world.despawn(e);
+        world.update_removed();
+        world.clear_trackers();
+        world.clear_trackers();
+        assert_eq!(world.removed::<Position>(), vec![e]);
+        let f = world.spawn().insert(Position(5)).id;
+        world.despawn(f);
+        world.update_removed();
+        world.update_removed();
+        assert!(world.removed::<Position>().is_empty());
+    }
+
+    #[test]
+    fn sync_world_across_threads() {
+        use std::{
+            rc::Rc,
+            sync::{Arc, RwLock},
+            thread,
+        };
+
+        #[derive(Debug, PartialEq)]
+        struct Position(u32);
+        struct Velocity(u32);
+        #[derive(Debug, PartialEq)]
+        struct Frames(u32);
+        #[derive(Debug, PartialEq)]
+        struct Moved(usize);
+
+        fn movement(query: Query<usize, (&mut Position, &Velocity)>, mut frames: ResMut<Frames>) {
+            for (_, (position, velocity)) in query {
+                position.0 += velocity.0;
+            }
+            frames.0 += 1;
+        }
+
+        let mut world = SyncWorld::new();
+        let a = world.spawn().insert(Position(0)).insert(Velocity(1)).id;
+        world.spawn().insert(Position(10));
+        world.insert_resource(Frames(0));
+
+        // Moved into a worker thread...
+        let mut world = thread::spawn(move || {
+            let mut schedule = Schedule::new();
+            schedule.add_system(Stage::Update, movement);
+            schedule.run(&mut world);
+            schedule.run(&mut world);
+            world.send_event(Moved(a));
+            world
+        })
+        .join()
+        .unwrap();
+        assert_eq!(world.component::<Position>(a), Some(&Position(2)));
+        assert_eq!(world.resource::<Frames>(), Some(&Frames(2)));
+        let mut reader = EventReader::<Moved>::new();
+        assert_eq!(reader.read(&world).collect::<Vec<_>>(), vec![&Moved(a)]);
+        assert_eq!(world.remove_component::<Velocity>(a).map(|v| v.0), Some(1));
+
+        // Shared behind a lock...
+        let world = Arc::new(RwLock::new(world));
+        let readers = (0..4)
+            .map(|_| {
+                let world = Arc::clone(&world);
+                thread::spawn(move || {
+                    let world = world.read().unwrap();
+                    let mut query = world.query();
+                    query
+                        .get::<&Position>()
+                        .unwrap()
+                        .map(|(_, p)| p.0)
+                        .sum::<u32>()
+                })
+            })
+            .collect::<Vec<_>>();
+        for reader in readers {
+            assert_eq!(reader.join().unwrap(), 12);
+        }
+
+        // The default world still stores `!Send` components...
+        let mut world = World::new();
+        let entity = world.spawn().insert(Rc::new(Position(1))).id;
+        assert_eq!(
+            world.component::<Rc<Position>>(entity).map(|p| p.0),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn spawn_batch() {
+        #[derive(Debug, PartialEq)]
+        struct Position(u32);
+        #[derive(Debug, PartialEq)]
+        struct Velocity(u32);
+        struct Frozen;
+
+        let mut world = World::new();
+        let first = world.spawn().insert(Position(0)).id;
+        let entities = world.spawn_batch((1..=100).map(|i| (Position(i), Velocity(i * 2))));
+        assert_eq!(entities.len(), 100);
+        assert!(!entities.contains(&first));
+        assert_eq!(
+            world.component::<Position>(entities[9]),
+            Some(&Position(10))
+        );
+        assert_eq!(
+            world.component::<Velocity>(entities[9]),
+            Some(&Velocity(20))
+        );
+        assert!(world.entities.0.capacity() >= 101);
+        let positions = storage::<Position>(&world).unwrap();
+        assert!(positions.capacity() >= 101);
+        assert_eq!(positions.len(), 101);
+
+        // Without a size hint, and with a table not existing yet...
+        let frozen = world.spawn_batch((0..10).filter(|i| i % 2 == 0).map(|_| (Frozen,)));
+        assert_eq!(frozen.len(), 5);
+        let mut query = world.query();
+        assert_eq!(query.with::<Frozen>().get::<&Frozen>().unwrap().count(), 5);
+        let mut query = world.query();
+        assert_eq!(query.get::<&Position>().unwrap().count(), 101);
+
+        // Thread-safe world...
+        let mut world = SyncWorld::new();
+        let entities = world.spawn_batch((0..3).map(|i| (Position(i),)));
+        assert_eq!(world.component::<Position>(entities[2]), Some(&Position(2)));
+    }
 }